@@ -27,3 +27,19 @@
 	# shellcheck disable=SC2046
 	# [ $(expr "$output" : '.*"message":"pod name invalid-pod-name is not accepted".*') -ne 0 ]
 }
+
+@test "Accept a valid deployment" {
+	run kwctl run  --request-path test_data/deployment_creation.json policy.wasm
+	[ "$status" -eq 0 ]
+	echo "$output"
+	# shellcheck disable=SC2046
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a cronjob without liveness probe" {
+	run kwctl run  --request-path test_data/cronjob_creation_invalid_liveness.json policy.wasm
+	[ "$status" -eq 0 ]
+	echo "$output"
+	# shellcheck disable=SC2046
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
@@ -0,0 +1,121 @@
+// Copyright (C) Nicolas Lamirault <nicolas.lamirault@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+// Matches `text` against a shell-like `pattern` where `*` matches any
+// sequence of characters and `?` matches a single character. A pattern
+// without wildcards is an exact match.
+pub(crate) fn matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in the pattern and of the text character it
+    // was matched against, to backtrack on a mismatch.
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+// Returns true if `text` matches any of `patterns`.
+pub(crate) fn matches_any(patterns: &[String], text: &str) -> bool {
+    patterns.iter().any(|pattern| matches(pattern, text))
+}
+
+// Returns true if some text matches both patterns. The patterns are walked
+// together as automata: `*` can stay in place or be skipped, and two
+// positions advance together when they can match the same character.
+pub(crate) fn intersects(a: &str, b: &str) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut visited = vec![vec![false; b.len() + 1]; a.len() + 1];
+    let mut pending = vec![(0, 0)];
+    while let Some((i, j)) = pending.pop() {
+        if visited[i][j] {
+            continue;
+        }
+        visited[i][j] = true;
+        if i == a.len() && j == b.len() {
+            return true;
+        }
+        if i < a.len() && a[i] == '*' {
+            pending.push((i + 1, j));
+        }
+        if j < b.len() && b[j] == '*' {
+            pending.push((i, j + 1));
+        }
+        if i < a.len() && j < b.len() {
+            let same_char = a[i] == b[j] || matches!(a[i], '?' | '*') || matches!(b[j], '?' | '*');
+            if same_char {
+                // A `*` consumes the character without moving on.
+                let next_i = if a[i] == '*' { i } else { i + 1 };
+                let next_j = if b[j] == '*' { j } else { j + 1 };
+                pending.push((next_i, next_j));
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_exact() {
+        assert!(matches("kube-system", "kube-system"));
+        assert!(!matches("kube-system", "kube-system-2"));
+        assert!(!matches("kube-system", "kube"));
+    }
+
+    #[test]
+    fn match_wildcards() {
+        assert!(matches("kube-*", "kube-system"));
+        assert!(matches("*-ci", "team-a-ci"));
+        assert!(matches("*", ""));
+        assert!(matches("team-?-ci", "team-a-ci"));
+        assert!(matches("a*b*c", "aXXbYYbZc"));
+        assert!(!matches("team-?-ci", "team-ab-ci"));
+        assert!(!matches("kube-*", "monitoring"));
+    }
+
+    #[test]
+    fn intersections() {
+        assert!(intersects("team-*", "*-ci"));
+        assert!(intersects("team-a", "team-*"));
+        assert!(intersects("kube-system", "kube-system"));
+        assert!(intersects("a?c", "*b*"));
+        assert!(intersects("*", ""));
+        assert!(!intersects("kube-*", "team-*"));
+        assert!(!intersects("team-a", "team-b"));
+        assert!(!intersects("a?c", "abbc"));
+        assert!(!intersects("kube-*", "monitoring"));
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright (C) Nicolas Lamirault <nicolas.lamirault@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::glob;
+
+const DEFAULT_REGISTRY: &str = "docker.io";
+const DEFAULT_NAMESPACE: &str = "library";
+
+// A container image reference, normalised the way the container runtime
+// resolves it: `nginx` is `docker.io/library/nginx`.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct ImageReference {
+    pub(crate) registry: String,
+    pub(crate) repository: String,
+    pub(crate) tag: Option<String>,
+    pub(crate) digest: Option<String>,
+}
+
+impl ImageReference {
+    pub(crate) fn parse(reference: &str) -> Result<ImageReference, String> {
+        let (name, digest) = match reference.split_once('@') {
+            Some((name, digest)) => (name, Some(String::from(digest))),
+            None => (reference, None),
+        };
+        // A colon after the last slash separates the tag, any other colon
+        // belongs to the registry port.
+        let last_component = name.rfind('/').map_or(0, |i| i + 1);
+        let (name, tag) = match name[last_component..].rfind(':') {
+            Some(i) => (
+                &name[..last_component + i],
+                Some(String::from(&name[last_component + i + 1..])),
+            ),
+            None => (name, None),
+        };
+        let (registry, repository) = match name.split_once('/') {
+            Some((first, rest))
+                if first.contains('.') || first.contains(':') || first == "localhost" =>
+            {
+                (String::from(first), String::from(rest))
+            }
+            _ => (String::from(DEFAULT_REGISTRY), String::from(name)),
+        };
+        let repository = if registry == DEFAULT_REGISTRY && !repository.contains('/') {
+            format!("{}/{}", DEFAULT_NAMESPACE, repository)
+        } else {
+            repository
+        };
+
+        if name.is_empty() || repository.split('/').any(|part| part.is_empty()) {
+            return Err(format!("invalid image reference {}", reference));
+        }
+        if tag.as_deref() == Some("") || digest.as_deref() == Some("") {
+            return Err(format!("invalid image reference {}", reference));
+        }
+        Ok(ImageReference {
+            registry,
+            repository,
+            tag,
+            digest,
+        })
+    }
+
+    // Returns true if the image matches the pattern. The registry,
+    // repository and tag of the pattern are glob patterns; a pattern without
+    // tag or digest matches any of them.
+    pub(crate) fn matches(&self, pattern: &ImageReference) -> bool {
+        if !glob::matches(&pattern.registry, &self.registry)
+            || !glob::matches(&pattern.repository, &self.repository)
+        {
+            return false;
+        }
+        if let Some(tag) = &pattern.tag {
+            if !self.tag.as_ref().is_some_and(|t| glob::matches(tag, t)) {
+                return false;
+            }
+        }
+        if let Some(digest) = &pattern.digest {
+            if self.digest.as_ref() != Some(digest) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+// Returns true if the image reference matches the pattern. Patterns that
+// cannot be parsed never match: they are rejected when the settings are
+// validated.
+pub(crate) fn matches(pattern: &str, image: &str) -> bool {
+    match (ImageReference::parse(pattern), ImageReference::parse(image)) {
+        (Ok(pattern), Ok(image)) => image.matches(&pattern),
+        _ => false,
+    }
+}
+
+// Returns true if the image reference matches any of the patterns.
+pub(crate) fn matches_any(patterns: &[String], image: &str) -> bool {
+    patterns.iter().any(|pattern| matches(pattern, image))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_normalise() {
+        let image = ImageReference::parse("nginx").unwrap();
+        assert_eq!(image.registry, "docker.io");
+        assert_eq!(image.repository, "library/nginx");
+        assert_eq!(image.tag, None);
+
+        let image = ImageReference::parse("registry.local:5000/team/app:1.2@sha256:abc").unwrap();
+        assert_eq!(image.registry, "registry.local:5000");
+        assert_eq!(image.repository, "team/app");
+        assert_eq!(image.tag.as_deref(), Some("1.2"));
+        assert_eq!(image.digest.as_deref(), Some("sha256:abc"));
+
+        assert!(ImageReference::parse("nginx:").is_err());
+        assert!(ImageReference::parse("").is_err());
+    }
+
+    #[test]
+    fn match_patterns() {
+        let patterns = vec![String::from("nginx")];
+        assert!(matches_any(&patterns, "nginx"));
+        assert!(matches_any(&patterns, "docker.io/library/nginx:1.25"));
+        assert!(!matches_any(&patterns, "ghcr.io/acme/nginx"));
+
+        let patterns = vec![String::from("ghcr.io/acme/*:v1.*")];
+        assert!(matches_any(&patterns, "ghcr.io/acme/proxy:v1.4"));
+        assert!(!matches_any(&patterns, "ghcr.io/acme/proxy:v2.0"));
+        assert!(!matches_any(&patterns, "ghcr.io/acme/proxy"));
+
+        let patterns = vec![String::from("istio/proxyv2@sha256:abc")];
+        assert!(matches_any(
+            &patterns,
+            "docker.io/istio/proxyv2:1.20@sha256:abc"
+        ));
+        assert!(!matches_any(&patterns, "istio/proxyv2:1.20"));
+    }
+}
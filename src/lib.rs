@@ -14,21 +14,40 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::{BTreeMap, HashSet};
+
 use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
 use lazy_static::lazy_static;
 
 use guest::prelude::*;
 use kubewarden_policy_sdk::wapc_guest as guest;
 
 use k8s_openapi::api::core::v1 as apicore;
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
 
 extern crate kubewarden_policy_sdk as kubewarden;
-use kubewarden::{logging, protocol_version_guest, request::ValidationRequest, validate_settings};
+use kubewarden::{
+    logging, protocol_version_guest, request::ValidationRequest, response::ValidationResponse,
+    validate_settings,
+};
 
+mod glob;
+mod image;
+mod probe;
+mod remediation;
+mod selector;
 mod settings;
-use settings::Settings;
+use probe::TimingField;
+use settings::{
+    Enforcement, EphemeralContainersMode, InitContainersMode, NonRestartingPodsLiveness, ProbeKind,
+    ProbePortsMode, Settings, StartupProbeMode,
+};
+
+mod violation;
+use violation::{escape_pointer_token, ContainerCategory, ContainerRef, Rule, Violation};
 
-use slog::{info, o, warn, Logger};
+use slog::{debug, info, o, warn, Logger};
 
 lazy_static! {
     static ref LOG_DRAIN: Logger = Logger::root(
@@ -44,118 +63,1082 @@ pub extern "C" fn wapc_init() {
     register_function("protocol_version", protocol_version_guest);
 }
 
-fn validate_container(container: &apicore::Container) -> Result<()> {
-    if container.liveness_probe.is_none() {
-        info!(
-            LOG_DRAIN,
-            "rejecting pod";
-            "container_name" => &container.name
-        );
-        return Err(anyhow!(
-            "container {} without liveness probe is not accepted",
-            &container.name
+// The admission request data `validate_pod` needs besides the pod spec.
+struct PodContext<'a> {
+    // JSON pointer to the pod spec in the admission object.
+    spec_pointer: &'static str,
+    namespace: &'a str,
+    // Name of the admission object.
+    name: &'a str,
+    sidecars: HashSet<String>,
+    user_groups: &'a HashSet<String>,
+    annotations: BTreeMap<String, String>,
+    // Probes required for the workload kind of the request.
+    required_probes: Vec<ProbeKind>,
+}
+
+impl PodContext<'_> {
+    // Builds a violation message from the rule template in the settings, or
+    // returns the default message when there is none.
+    fn message(
+        &self,
+        settings: &Settings,
+        rule: Rule,
+        container: &str,
+        probe: Option<ProbeKind>,
+        default: String,
+    ) -> String {
+        match settings.message_template(rule) {
+            Some(template) => violation::render_template(
+                template,
+                &[
+                    ("container", container),
+                    ("namespace", self.namespace),
+                    ("probe", &probe.map(|p| p.to_string()).unwrap_or_default()),
+                    ("pod", self.name),
+                ],
+            ),
+            None => default,
+        }
+    }
+}
+
+// Returns a probe snippet for the container when remediation snippets are
+// enabled.
+fn remediation(
+    container: &apicore::Container,
+    kind: ProbeKind,
+    settings: &Settings,
+) -> Option<String> {
+    settings
+        .remediation_snippets
+        .then(|| remediation::probe_snippet(container, kind))
+}
+
+fn validate_container(
+    container: &apicore::Container,
+    reference: &ContainerRef,
+    required: &[ProbeKind],
+    context: &PodContext,
+    settings: &Settings,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    if let Some(image) = &container.image {
+        if image::matches_any(&settings.excluded_images, image) {
+            debug!(
+                LOG_DRAIN,
+                "skipping excluded image";
+                "container_name" => &container.name,
+                "image" => image
+            );
+            return violations;
+        }
+    }
+    for kind in required {
+        if kind.probe(container).is_none() {
+            let rule = Rule::missing_probe(*kind);
+            violations.push(
+                Violation::for_container(
+                    reference,
+                    rule,
+                    Some(*kind),
+                    context.message(
+                        settings,
+                        rule,
+                        &container.name,
+                        Some(*kind),
+                        format!(
+                            "container {} without {} probe is not accepted",
+                            &container.name, kind
+                        ),
+                    ),
+                )
+                .with_remediation(remediation(container, *kind, settings)),
+            );
+        }
+    }
+    if !required.contains(&ProbeKind::Startup) {
+        violations.extend(validate_startup_probe(
+            container, reference, context, settings,
         ));
     }
-    if container.readiness_probe.is_none() {
-        info!(
-            LOG_DRAIN,
-            "rejecting pod";
-            "container_name" => &container.name
+    violations.extend(validate_probe_timings(
+        container, reference, context, settings,
+    ));
+    violations.extend(validate_probe_relations(
+        container, reference, context, settings,
+    ));
+    violations.extend(validate_startup_budget(
+        container, reference, context, settings,
+    ));
+    violations.extend(validate_probe_ports(
+        container, reference, context, settings,
+    ));
+    violations
+}
+
+fn validate_startup_probe(
+    container: &apicore::Container,
+    reference: &ContainerRef,
+    context: &PodContext,
+    settings: &Settings,
+) -> Option<Violation> {
+    if container.startup_probe.is_some() {
+        return None;
+    }
+    let (rule, default) = match settings.startup_probe_mode {
+        StartupProbeMode::Disabled => return None,
+        StartupProbeMode::Required => (
+            Rule::MissingStartupProbe,
+            format!(
+                "container {} without startup probe is not accepted: startup probes are required",
+                &container.name
+            ),
+        ),
+        StartupProbeMode::SlowStart => {
+            let initial_delay = container
+                .liveness_probe
+                .as_ref()
+                .and_then(|probe| probe.initial_delay_seconds)
+                .unwrap_or(0);
+            if initial_delay <= settings.startup_probe_delay_threshold {
+                return None;
+            }
+            (
+                Rule::SlowStartWithoutStartupProbe,
+                format!(
+                    "container {} with a liveness probe initial delay of {}s (above {}s) requires a startup probe",
+                    &container.name,
+                    initial_delay,
+                    settings.startup_probe_delay_threshold
+                ),
+            )
+        }
+    };
+    Some(
+        Violation::for_container(
+            reference,
+            rule,
+            Some(ProbeKind::Startup),
+            context.message(
+                settings,
+                rule,
+                &container.name,
+                Some(ProbeKind::Startup),
+                default,
+            ),
+        )
+        .with_remediation(remediation(container, ProbeKind::Startup, settings)),
+    )
+}
+
+// Checks the timing fields of the probes the container defines against the
+// `probe_bounds` of their kind.
+fn validate_probe_timings(
+    container: &apicore::Container,
+    reference: &ContainerRef,
+    context: &PodContext,
+    settings: &Settings,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for kind in ProbeKind::ALL {
+        let (probe, bounds) = match (kind.probe(container), settings.probe_bounds(kind)) {
+            (Some(probe), Some(bounds)) => (probe, bounds),
+            _ => continue,
+        };
+        for field in TimingField::ALL {
+            let value = field.value(probe);
+            let bounds = bounds.get(field);
+            let default = match (bounds.min, bounds.max) {
+                (Some(min), _) if value < min => format!(
+                    "container {} {} probe {} of {} is below the minimum of {}",
+                    &container.name, kind, field, value, min
+                ),
+                (_, Some(max)) if value > max => format!(
+                    "container {} {} probe {} of {} is above the maximum of {}",
+                    &container.name, kind, field, value, max
+                ),
+                _ => continue,
+            };
+            let rule = Rule::ProbeTimingOutOfBounds;
+            violations.push(
+                Violation::for_container(
+                    reference,
+                    rule,
+                    Some(kind),
+                    context.message(settings, rule, &container.name, Some(kind), default),
+                )
+                .with_field(field.field()),
+            );
+        }
+    }
+    violations
+}
+
+// Performs the relational checks between probe fields enabled by
+// `cross_field_checks`.
+fn validate_probe_relations(
+    container: &apicore::Container,
+    reference: &ContainerRef,
+    context: &PodContext,
+    settings: &Settings,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut push = |rule: Rule, kind: ProbeKind, field: TimingField, default: String| {
+        violations.push(
+            Violation::for_container(
+                reference,
+                rule,
+                Some(kind),
+                context.message(settings, rule, &container.name, Some(kind), default),
+            )
+            .with_field(field.field()),
         );
-        return Err(anyhow!(
-            "container {} without readiness probe is not accepted",
-            &container.name
+    };
+    for kind in ProbeKind::ALL {
+        let probe = match kind.probe(container) {
+            Some(probe) => probe,
+            None => continue,
+        };
+        let timeout = TimingField::TimeoutSeconds.value(probe);
+        let period = TimingField::PeriodSeconds.value(probe);
+        if settings.cross_field_check(Rule::TimeoutNotBelowPeriod) && timeout >= period {
+            push(
+                Rule::TimeoutNotBelowPeriod,
+                kind,
+                TimingField::TimeoutSeconds,
+                format!(
+                    "container {} {} probe timeoutSeconds of {} is not below its periodSeconds of {}",
+                    &container.name, kind, timeout, period
+                ),
+            );
+        }
+        let success_threshold = TimingField::SuccessThreshold.value(probe);
+        if settings.cross_field_check(Rule::SuccessThresholdNotOne)
+            && kind != ProbeKind::Readiness
+            && success_threshold != 1
+        {
+            push(
+                Rule::SuccessThresholdNotOne,
+                kind,
+                TimingField::SuccessThreshold,
+                format!(
+                    "container {} {} probe successThreshold of {} must be 1",
+                    &container.name, kind, success_threshold
+                ),
+            );
+        }
+    }
+    if settings.cross_field_check(Rule::LivenessWindowNotAboveReadiness) {
+        if let (Some(liveness), Some(readiness)) = (
+            container.liveness_probe.as_ref(),
+            container.readiness_probe.as_ref(),
+        ) {
+            // How long a failing probe takes to trigger its action, widened so
+            // that large thresholds cannot overflow.
+            let window = |probe| {
+                i64::from(TimingField::PeriodSeconds.value(probe))
+                    * i64::from(TimingField::FailureThreshold.value(probe))
+            };
+            if window(liveness) <= window(readiness) {
+                push(
+                    Rule::LivenessWindowNotAboveReadiness,
+                    ProbeKind::Liveness,
+                    TimingField::FailureThreshold,
+                    format!(
+                        "container {} liveness failure window of {}s is not above the readiness failure window of {}s: \
+                         the container would be restarted before being taken out of rotation",
+                        &container.name,
+                        window(liveness),
+                        window(readiness)
+                    ),
+                );
+            }
+        }
+    }
+    violations
+}
+
+// Checks that the container gets at least its startup budget to start before
+// the liveness probe can restart it: through the startup probe when it has
+// one, through the liveness probe initial delay otherwise.
+fn validate_startup_budget(
+    container: &apicore::Container,
+    reference: &ContainerRef,
+    context: &PodContext,
+    settings: &Settings,
+) -> Option<Violation> {
+    let minimum = settings.startup_budget(container.image.as_deref());
+    if minimum == 0 {
+        return None;
+    }
+    let (kind, field, default) = match (&container.startup_probe, &container.liveness_probe) {
+        (Some(startup), _) => {
+            // Widened so that large probe fields cannot overflow.
+            let budget = i64::from(TimingField::InitialDelaySeconds.value(startup))
+                + i64::from(TimingField::PeriodSeconds.value(startup))
+                    * i64::from(TimingField::FailureThreshold.value(startup));
+            if budget >= i64::from(minimum) {
+                return None;
+            }
+            (
+                ProbeKind::Startup,
+                None,
+                format!(
+                    "container {} startup probe budget of {}s \
+                     (initialDelaySeconds + periodSeconds * failureThreshold) is below the minimum of {}s",
+                    &container.name, budget, minimum
+                ),
+            )
+        }
+        (None, Some(liveness)) => {
+            let initial_delay = TimingField::InitialDelaySeconds.value(liveness);
+            if initial_delay >= minimum {
+                return None;
+            }
+            (
+                ProbeKind::Liveness,
+                Some(TimingField::InitialDelaySeconds),
+                format!(
+                    "container {} without startup probe has a liveness probe initialDelaySeconds of {} \
+                     below the startup budget of {}s",
+                    &container.name, initial_delay, minimum
+                ),
+            )
+        }
+        (None, None) => return None,
+    };
+    let rule = Rule::StartupBudgetTooShort;
+    let violation = Violation::for_container(
+        reference,
+        rule,
+        Some(kind),
+        context.message(settings, rule, &container.name, Some(kind), default),
+    );
+    Some(match field {
+        Some(field) => violation.with_field(field.field()),
+        None => violation,
+    })
+}
+
+// Returns the problem with a probe port, if any: a port out of range or with a
+// malformed name, a port only declared for UDP or SCTP, or a port not declared
+// by the container as configured by `probe_ports`.
+fn probe_port_problem(
+    container: &apicore::Container,
+    kind: ProbeKind,
+    port: &IntOrString,
+    settings: &Settings,
+) -> Option<(Rule, String)> {
+    let declared: Vec<&apicore::ContainerPort> = container
+        .ports
+        .iter()
+        .flatten()
+        .filter(|p| match port {
+            IntOrString::Int(number) => p.container_port == *number,
+            IntOrString::String(name) => p.name.as_ref() == Some(name),
+        })
+        .collect();
+    match port {
+        IntOrString::Int(number) if !(1..=65535).contains(number) => {
+            return Some((
+                Rule::InvalidProbePort,
+                format!(
+                    "container {} {} probe port {} is not between 1 and 65535",
+                    &container.name, kind, number
+                ),
+            ));
+        }
+        IntOrString::String(name) if !probe::valid_port_name(name) => {
+            return Some((
+                Rule::InvalidProbePort,
+                format!(
+                    "container {} {} probe port {} is not a valid port name: \
+                     at most 15 lowercase letters, digits and single hyphens, with at least one letter",
+                    &container.name, kind, name
+                ),
+            ));
+        }
+        _ => {}
+    }
+    if !declared.is_empty() {
+        let tcp = |p: &&apicore::ContainerPort| p.protocol.as_deref().is_none_or(|p| p == "TCP");
+        if declared.iter().any(tcp) {
+            return None;
+        }
+        let port = match port {
+            IntOrString::Int(number) => number.to_string(),
+            IntOrString::String(name) => name.clone(),
+        };
+        let protocols: Vec<&str> = declared
+            .iter()
+            .filter_map(|p| p.protocol.as_deref())
+            .collect();
+        return Some((
+            Rule::NonTcpProbePort,
+            format!(
+                "container {} {} probe port {} is only declared for {}: probes connect over TCP",
+                &container.name,
+                kind,
+                port,
+                protocols.join(", ")
+            ),
         ));
     }
-    Ok(())
+    match (port, settings.probe_ports) {
+        (_, ProbePortsMode::Ignore) | (IntOrString::Int(_), ProbePortsMode::Named) => None,
+        (IntOrString::String(name), _) => Some((
+            Rule::UndeclaredProbePort,
+            format!(
+                "container {} {} probe port {} does not match the name of a declared container port",
+                &container.name, kind, name
+            ),
+        )),
+        (IntOrString::Int(number), ProbePortsMode::All) => Some((
+            Rule::UndeclaredProbePort,
+            format!(
+                "container {} {} probe port {} is not a declared container port",
+                &container.name, kind, number
+            ),
+        )),
+    }
 }
 
-fn validate_ephemeral_container(container: &apicore::EphemeralContainer) -> Result<()> {
-    if container.liveness_probe.is_none() {
-        info!(
+// Checks the ports of the probes the container defines. A probe on an
+// invalid, UDP or SCTP only, or undeclared named port can never succeed.
+fn validate_probe_ports(
+    container: &apicore::Container,
+    reference: &ContainerRef,
+    context: &PodContext,
+    settings: &Settings,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for kind in ProbeKind::ALL {
+        let (field, port) = match kind.probe(container).and_then(probe::port) {
+            Some(port) => port,
+            None => continue,
+        };
+        if let Some((rule, default)) = probe_port_problem(container, kind, &port, settings) {
+            violations.push(
+                Violation::for_container(
+                    reference,
+                    rule,
+                    Some(kind),
+                    context.message(settings, rule, &container.name, Some(kind), default),
+                )
+                .with_field(field),
+            );
+        }
+    }
+    violations
+}
+
+// Ephemeral containers cannot define probes, so they are only checked against
+// the `ephemeral_containers` mode.
+fn validate_ephemeral_container(
+    container: &apicore::EphemeralContainer,
+    reference: &ContainerRef,
+    context: &PodContext,
+    settings: &Settings,
+) -> Option<Violation> {
+    let allowed = match settings.ephemeral_containers {
+        EphemeralContainersMode::Allow => true,
+        EphemeralContainersMode::Reject => false,
+        EphemeralContainersMode::AllowGroups => settings
+            .ephemeral_containers_allowed_groups
+            .iter()
+            .any(|group| context.user_groups.contains(group)),
+    };
+    if allowed {
+        return None;
+    }
+    let rule = Rule::EphemeralContainerNotAllowed;
+    Some(Violation::for_container(
+        reference,
+        rule,
+        None,
+        context.message(
+            settings,
+            rule,
+            &container.name,
+            None,
+            format!("ephemeral container {} is not allowed", &container.name),
+        ),
+    ))
+}
+
+// Subresource `kubectl debug` updates to add ephemeral containers.
+const EPHEMERAL_CONTAINERS_SUBRESOURCE: &str = "ephemeralcontainers";
+
+// JSON pointer to the pod spec inside an admission object of the given kind.
+fn pod_spec_pointer(kind: &str) -> &'static str {
+    match kind {
+        "Pod" => "/spec",
+        "CronJob" => "/spec/jobTemplate/spec/template/spec",
+        _ => "/spec/template/spec",
+    }
+}
+
+// JSON pointer to the pod metadata, the one of the pod template for workload
+// kinds, inside an admission object of the given kind.
+fn pod_metadata_pointer(kind: &str) -> String {
+    let template = pod_spec_pointer(kind)
+        .strip_suffix("/spec")
+        .unwrap_or_default();
+    format!("{}/metadata", template)
+}
+
+// Label the Deployment controller sets on the ReplicaSets it manages and on
+// their pods.
+const POD_TEMPLATE_HASH_LABEL: &str = "pod-template-hash";
+
+// The workload kind the object belongs to, resolved from its controller owner
+// reference so that pods and ReplicaSets or Jobs created by a workload get the
+// probes required for that workload. A pod owned by a ReplicaSet belongs to a
+// Deployment when it carries the pod template hash label. The owner of the
+// owner cannot be resolved: pods of the Jobs of a CronJob belong to `Job`.
+fn workload_kind<'a>(kind: &'a str, object: &'a serde_json::Value) -> &'a str {
+    let owner = object
+        .pointer("/metadata/ownerReferences")
+        .and_then(|owners| owners.as_array())
+        .and_then(|owners| {
+            owners
+                .iter()
+                .find(|owner| owner.get("controller").and_then(|c| c.as_bool()) == Some(true))
+        })
+        .and_then(|owner| owner.get("kind"))
+        .and_then(|kind| kind.as_str());
+    match (kind, owner) {
+        ("Pod", Some("ReplicaSet")) => {
+            let hashed = object
+                .pointer(&format!("/metadata/labels/{}", POD_TEMPLATE_HASH_LABEL))
+                .is_some();
+            if hashed {
+                "Deployment"
+            } else {
+                "ReplicaSet"
+            }
+        }
+        ("Pod", Some(owner @ ("StatefulSet" | "DaemonSet" | "Job")))
+        | ("ReplicaSet", Some(owner @ "Deployment"))
+        | ("Job", Some(owner @ "CronJob")) => owner,
+        _ => kind,
+    }
+}
+
+// Names of the init containers declared with `restartPolicy: Always`, the
+// native sidecar pattern. The field is read from the raw object because the
+// Kubernetes API bindings in use predate it.
+fn restartable_init_containers(object: &serde_json::Value, kind: &str) -> HashSet<String> {
+    let pointer = format!("{}/initContainers", pod_spec_pointer(kind));
+    object
+        .pointer(&pointer)
+        .and_then(|containers| containers.as_array())
+        .map(|containers| {
+            containers
+                .iter()
+                .filter(|container| {
+                    container.get("restartPolicy").and_then(|p| p.as_str()) == Some("Always")
+                })
+                .filter_map(|container| container.get("name").and_then(|n| n.as_str()))
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Annotation holding the mandatory justification of annotation exemptions.
+const JUSTIFICATION_ANNOTATION: &str = "probes-policy.kubewarden.io/justification";
+
+// Annotation holding the RFC 3339 deadline of annotation exemptions.
+const EXEMPT_UNTIL_ANNOTATION: &str = "probes-policy.kubewarden.io/exempt-until";
+
+// Where annotation exemptions stand relative to their deadline.
+#[derive(Debug, PartialEq)]
+enum ExemptionDeadline {
+    // No deadline, or a deadline beyond the warning window.
+    Active,
+    // The deadline falls within `exemption_expiry_warning_days`.
+    ExpiresSoon(DateTime<Utc>),
+    Expired(DateTime<Utc>),
+}
+
+fn exemption_deadline(
+    annotations: &BTreeMap<String, String>,
+    settings: &Settings,
+    now: DateTime<Utc>,
+) -> Result<ExemptionDeadline> {
+    if !settings
+        .exemption_annotations
+        .iter()
+        .any(|key| annotations.contains_key(key))
+    {
+        return Ok(ExemptionDeadline::Active);
+    }
+    let deadline = match annotations.get(EXEMPT_UNTIL_ANNOTATION) {
+        Some(deadline) => DateTime::parse_from_rfc3339(deadline.trim())
+            .map_err(|e| {
+                anyhow!(
+                    "annotation {} must be an RFC 3339 timestamp, got {:?}: {}",
+                    EXEMPT_UNTIL_ANNOTATION,
+                    deadline,
+                    e
+                )
+            })?
+            .with_timezone(&Utc),
+        None => return Ok(ExemptionDeadline::Active),
+    };
+    if deadline <= now {
+        return Ok(ExemptionDeadline::Expired(deadline));
+    }
+    if deadline <= now + Duration::days(settings.exemption_expiry_warning_days) {
+        return Ok(ExemptionDeadline::ExpiresSoon(deadline));
+    }
+    Ok(ExemptionDeadline::Active)
+}
+
+// Annotations of the pod, read from the pod template for workload kinds.
+fn pod_annotations(object: &serde_json::Value, kind: &str) -> BTreeMap<String, String> {
+    object
+        .pointer(&format!("{}/annotations", pod_metadata_pointer(kind)))
+        .and_then(|annotations| serde_json::from_value(annotations.clone()).ok())
+        .unwrap_or_default()
+}
+
+// Names of the containers exempted through the annotations listed in
+// `exemption_annotations`. Exemptions are only honored with a non-empty
+// justification annotation and every one of them is logged for auditing.
+fn annotation_exemptions(
+    annotations: &BTreeMap<String, String>,
+    settings: &Settings,
+) -> HashSet<String> {
+    let mut exempted = HashSet::new();
+    let requested: Vec<(&String, &String)> = settings
+        .exemption_annotations
+        .iter()
+        .filter_map(|key| annotations.get_key_value(key))
+        .collect();
+    if requested.is_empty() {
+        return exempted;
+    }
+    let justification = annotations
+        .get(JUSTIFICATION_ANNOTATION)
+        .map(|justification| justification.trim())
+        .unwrap_or_default();
+    if justification.is_empty() {
+        warn!(
             LOG_DRAIN,
-            "rejecting pod";
-            "container_name" => &container.name
+            "ignoring exemption annotations without justification";
+            "justification_annotation" => JUSTIFICATION_ANNOTATION
+        );
+        return exempted;
+    }
+    for (annotation, containers) in requested {
+        for name in containers
+            .split(',')
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+        {
+            info!(
+                LOG_DRAIN,
+                "exempting container";
+                "container_name" => name,
+                "annotation" => annotation,
+                "justification" => justification
+            );
+            exempted.insert(String::from(name));
+        }
+    }
+    exempted
+}
+
+// Returns true if the container name matches one of `excluded_containers` or
+// is exempted through annotations. Exclusions only waive the probe rules.
+fn container_excluded(
+    reference: &ContainerRef,
+    settings: &Settings,
+    exempted: &HashSet<String>,
+) -> bool {
+    let name = reference.name.as_str();
+    if !glob::matches_any(&settings.excluded_containers, name) && !exempted.contains(name) {
+        return false;
+    }
+    debug!(
+        LOG_DRAIN,
+        "skipping excluded container";
+        "container_category" => reference.category.to_string(),
+        "container_name" => name
+    );
+    true
+}
+
+// Probes the containers of the pod must define. The liveness probe can be
+// waived for run-to-completion pods, with a `restartPolicy` of `Never` or
+// `OnFailure`, as configured by `non_restarting_pods_liveness`. Native
+// sidecars are restarted regardless of the pod restart policy and keep the
+// probes of the workload kind.
+fn pod_required_probes(
+    pod: &apicore::PodSpec,
+    context: &PodContext,
+    settings: &Settings,
+) -> Vec<ProbeKind> {
+    let restart_policy = pod.restart_policy.as_deref().unwrap_or("Always");
+    if restart_policy == "Always" {
+        return context.required_probes.clone();
+    }
+    let waived = match settings.non_restarting_pods_liveness {
+        NonRestartingPodsLiveness::Required => false,
+        NonRestartingPodsLiveness::Waived => true,
+        NonRestartingPodsLiveness::WithoutDeadline => pod.active_deadline_seconds.is_some(),
+    };
+    if !waived {
+        return context.required_probes.clone();
+    }
+    info!(
+        LOG_DRAIN,
+        "waiving liveness probe requirement";
+        "restart_policy" => restart_policy
+    );
+    context
+        .required_probes
+        .iter()
+        .copied()
+        .filter(|kind| *kind != ProbeKind::Liveness)
+        .collect()
+}
+
+fn validate_pod(
+    pod: &apicore::PodSpec,
+    context: &PodContext,
+    settings: &Settings,
+    exempted: &HashSet<String>,
+) -> Vec<Violation> {
+    let required = pod_required_probes(pod, context, settings);
+    let mut violations = Vec::new();
+    for (index, container) in pod.containers.iter().enumerate() {
+        let reference = ContainerRef::new(
+            context.spec_pointer,
+            ContainerCategory::Container,
+            index,
+            &container.name,
         );
-        return Err(anyhow!(
-            "container {} without liveness probe is not accepted",
-            &container.name
+        if container_excluded(&reference, settings, exempted) {
+            continue;
+        }
+        violations.extend(validate_container(
+            container, &reference, &required, context, settings,
         ));
     }
-    if container.readiness_probe.is_none() {
-        info!(
-            LOG_DRAIN,
-            "rejecting pod";
-            "container_name" => &container.name
+    for (index, container) in pod.init_containers.iter().flatten().enumerate() {
+        let reference = ContainerRef::new(
+            context.spec_pointer,
+            ContainerCategory::InitContainer,
+            index,
+            &container.name,
         );
-        return Err(anyhow!(
-            "container {} without readiness probe is not accepted",
-            &container.name
+        if container_excluded(&reference, settings, exempted) {
+            continue;
+        }
+        let sidecar = context.sidecars.contains(&container.name);
+        let checked = match settings.init_containers {
+            InitContainersMode::Skip => false,
+            InitContainersMode::SidecarsOnly => sidecar,
+            InitContainersMode::Strict => true,
+        };
+        if !checked {
+            info!(
+                LOG_DRAIN,
+                "skipping init container";
+                "container_name" => &container.name
+            );
+            continue;
+        }
+        let required = if sidecar {
+            &context.required_probes
+        } else {
+            &required
+        };
+        violations.extend(validate_container(
+            container, &reference, required, context, settings,
         ));
     }
-    Ok(())
+    violations.extend(validate_ephemeral_containers(pod, context, settings));
+    violations
 }
 
-fn validate_pod(pod: &apicore::PodSpec) -> Result<()> {
-    let mut err_message = String::new();
-    for container in &pod.containers {
-        let container_valid = validate_container(container);
-        if container_valid.is_err() {
-            err_message = err_message
-                + &format!(
-                    "container {} is invalid: {}\n",
-                    container.name,
-                    container_valid.unwrap_err()
-                );
-        }
+// Exclusions only waive the probe rules: the ephemeral containers mode is an
+// authorization control and always applies.
+fn validate_ephemeral_containers(
+    pod: &apicore::PodSpec,
+    context: &PodContext,
+    settings: &Settings,
+) -> Vec<Violation> {
+    pod.ephemeral_containers
+        .iter()
+        .flatten()
+        .enumerate()
+        .filter_map(|(index, container)| {
+            let reference = ContainerRef::new(
+                context.spec_pointer,
+                ContainerCategory::EphemeralContainer,
+                index,
+                &container.name,
+            );
+            validate_ephemeral_container(container, &reference, context, settings)
+        })
+        .collect()
+}
+
+// Namespace of the request, falling back to the object metadata when the
+// request does not carry it.
+fn request_namespace(validation_request: &ValidationRequest<Settings>) -> &str {
+    if !validation_request.request.namespace.is_empty() {
+        return &validation_request.request.namespace;
     }
-    if let Some(init_containers) = &pod.init_containers {
-        for container in init_containers {
-            let container_valid = validate_container(container);
-            if container_valid.is_err() {
-                err_message = err_message
-                    + &format!(
-                        "init container {} is invalid: {}\n",
-                        container.name,
-                        container_valid.unwrap_err()
-                    );
-            }
-        }
+    validation_request
+        .request
+        .object
+        .pointer("/metadata/namespace")
+        .and_then(|namespace| namespace.as_str())
+        .unwrap_or_default()
+}
+
+// Name of the admission object, falling back to the request name, then to
+// the `generateName` prefix of objects not named yet.
+fn object_name(validation_request: &ValidationRequest<Settings>) -> &str {
+    let object = &validation_request.request.object;
+    object
+        .pointer("/metadata/name")
+        .and_then(|name| name.as_str())
+        .or_else(|| Some(validation_request.request.name.as_str()).filter(|name| !name.is_empty()))
+        .or_else(|| {
+            object
+                .pointer("/metadata/generateName")
+                .and_then(|name| name.as_str())
+        })
+        .filter(|name| !name.is_empty())
+        .unwrap_or("<unnamed>")
+}
+
+// Returns true if the request updates the object without changing its pod
+// spec, labels or annotations, e.g. when scaling a workload or removing its
+// finalizers: the pod template was already checked, or predates the policy.
+fn pod_template_unchanged(validation_request: &ValidationRequest<Settings>, kind: &str) -> bool {
+    let request = &validation_request.request;
+    if request.operation != "UPDATE" || request.old_object.is_null() {
+        return false;
     }
-    if let Some(ephemeral_containers) = &pod.ephemeral_containers {
-        for container in ephemeral_containers {
-            let container_valid = validate_ephemeral_container(container);
-            if container_valid.is_err() {
-                err_message = err_message
-                    + &format!(
-                        "ephemeral container {} is invalid: {}\n",
-                        container.name,
-                        container_valid.unwrap_err()
-                    );
-            }
-        }
+    let metadata = pod_metadata_pointer(kind);
+    [
+        String::from(pod_spec_pointer(kind)),
+        format!("{}/labels", metadata),
+        format!("{}/annotations", metadata),
+    ]
+    .iter()
+    .all(|pointer| request.object.pointer(pointer) == request.old_object.pointer(pointer))
+}
+
+// Labels of the pod, read from the pod template for workload kinds.
+fn pod_labels(object: &serde_json::Value, kind: &str) -> BTreeMap<String, String> {
+    object
+        .pointer(&format!("{}/labels", pod_metadata_pointer(kind)))
+        .and_then(|labels| serde_json::from_value(labels.clone()).ok())
+        .unwrap_or_default()
+}
+
+// Same as `kubewarden::accept_request`, returning the warnings to the client.
+fn accept_request_with_warnings(warnings: Vec<String>) -> CallResult {
+    if warnings.is_empty() {
+        return kubewarden::accept_request();
+    }
+    Ok(serde_json::to_vec(&ValidationResponse {
+        accepted: true,
+        message: None,
+        code: None,
+        mutated_object: None,
+        audit_annotations: None,
+        warnings: Some(warnings),
+    })?)
+}
+
+// Turns the violations into a response according to the enforcement mode.
+// Every violation is logged as a structured record.
+fn enforce(
+    violations: &[Violation],
+    settings: &Settings,
+    enforcement: Enforcement,
+    mut warnings: Vec<String>,
+    request_uid: &str,
+) -> CallResult {
+    for violation in violations {
+        let container = violation.container.as_ref();
+        info!(
+            LOG_DRAIN,
+            "policy violation";
+            "enforcement" => enforcement.to_string(),
+            "request_uid" => request_uid,
+            "rule_id" => violation.rule.id(),
+            "rule" => violation.rule.name(),
+            "container_category" => container.map(|c| c.category.to_string()),
+            "container_index" => container.map(|c| c.index),
+            "container_name" => container.map(|c| c.name.as_str()),
+            "probe" => violation.probe.map(|p| p.to_string()),
+            "path" => &violation.path,
+            "message" => &violation.message
+        );
     }
-    if err_message.is_empty() {
-        return Ok(());
+    match enforcement {
+        Enforcement::Deny => kubewarden::reject_request(
+            Some(violation::render(
+                violations,
+                settings.documentation_url.as_deref(),
+            )),
+            None,
+            None,
+            (!warnings.is_empty()).then_some(warnings),
+        ),
+        Enforcement::Warn => {
+            warnings.extend(
+                violations
+                    .iter()
+                    .map(|violation| violation.describe(settings.documentation_url.as_deref())),
+            );
+            accept_request_with_warnings(warnings)
+        }
+        Enforcement::Dryrun => accept_request_with_warnings(warnings),
     }
-    Err(anyhow!(err_message))
 }
 
 fn validate(payload: &[u8]) -> CallResult {
+    validate_at(payload, Utc::now())
+}
+
+// Validates the request at `now`, the time exemption deadlines and
+// `enforce_after` are compared with.
+fn validate_at(payload: &[u8], now: DateTime<Utc>) -> CallResult {
     let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
 
     info!(LOG_DRAIN, "starting validation");
+    let namespace = request_namespace(&validation_request);
+    if !validation_request.settings.namespace_in_scope(namespace) {
+        info!(
+            LOG_DRAIN,
+            "skipping namespace";
+            "namespace" => namespace
+        );
+        return kubewarden::accept_request();
+    }
+    if validation_request
+        .request
+        .object
+        .pointer("/metadata/deletionTimestamp")
+        .is_some_and(|timestamp| !timestamp.is_null())
+    {
+        info!(LOG_DRAIN, "skipping object being deleted");
+        return kubewarden::accept_request();
+    }
+    let kind = validation_request.request.kind.kind.as_str();
+    if pod_template_unchanged(&validation_request, kind) {
+        info!(
+            LOG_DRAIN,
+            "skipping update leaving the pod template unchanged"
+        );
+        return kubewarden::accept_request();
+    }
+    if !validation_request
+        .settings
+        .labels_in_scope(&pod_labels(&validation_request.request.object, kind))
+    {
+        info!(LOG_DRAIN, "skipping pod not matching the label selectors");
+        return kubewarden::accept_request();
+    }
+    let settings = &validation_request.settings;
+    let mut context = PodContext {
+        spec_pointer: pod_spec_pointer(kind),
+        namespace,
+        name: object_name(&validation_request),
+        sidecars: restartable_init_containers(&validation_request.request.object, kind),
+        user_groups: &validation_request.request.user_info.groups,
+        annotations: pod_annotations(&validation_request.request.object, kind),
+        required_probes: settings
+            .required_probe_kinds(workload_kind(kind, &validation_request.request.object)),
+    };
+    // `kubectl debug` only adds ephemeral containers to a running pod through
+    // this subresource: the rest of the spec cannot change, so only the
+    // ephemeral containers mode applies.
+    let ephemeral_only =
+        validation_request.request.sub_resource == EPHEMERAL_CONTAINERS_SUBRESOURCE;
+    let mut warnings = Vec::new();
+    let mut violations = Vec::new();
+    let mut expires_soon = None;
+    let enforcement = settings.effective_enforcement(now);
+    let deadline = if ephemeral_only {
+        Ok(ExemptionDeadline::Active)
+    } else {
+        exemption_deadline(&context.annotations, settings, now)
+    };
+    match deadline {
+        Ok(ExemptionDeadline::Active) => {}
+        Ok(ExemptionDeadline::ExpiresSoon(deadline)) => expires_soon = Some(deadline),
+        Ok(ExemptionDeadline::Expired(deadline)) => {
+            info!(
+                LOG_DRAIN,
+                "ignoring expired exemption annotations";
+                "exempt_until" => deadline.to_rfc3339()
+            );
+            context
+                .annotations
+                .retain(|key, _| !settings.exemption_annotations.contains(key));
+        }
+        Err(err) => {
+            let rule = Rule::InvalidExemptionDeadline;
+            violations.push(Violation::for_pod(
+                rule,
+                format!(
+                    "{}/annotations/{}",
+                    pod_metadata_pointer(kind),
+                    escape_pointer_token(EXEMPT_UNTIL_ANNOTATION)
+                ),
+                context.message(settings, rule, "", None, err.to_string()),
+            ));
+            context
+                .annotations
+                .retain(|key, _| !settings.exemption_annotations.contains(key));
+        }
+    }
+    let exempted = if ephemeral_only {
+        HashSet::new()
+    } else {
+        annotation_exemptions(&context.annotations, settings)
+    };
+    // Only warn about exemptions that actually exempt a container.
+    if let Some(deadline) = expires_soon.filter(|_| !exempted.is_empty()) {
+        warnings.push(format!(
+            "probe exemptions expire on {}",
+            deadline.to_rfc3339()
+        ));
+    }
     match validation_request.extract_pod_spec_from_object() {
         Ok(pod_spec) => {
             if let Some(pod_spec) = pod_spec {
-                return match validate_pod(&pod_spec) {
-                    Ok(_) => kubewarden::accept_request(),
-                    Err(err) => kubewarden::reject_request(Some(err.to_string()), None, None, None),
-                };
+                if ephemeral_only {
+                    violations.extend(validate_ephemeral_containers(&pod_spec, &context, settings));
+                } else {
+                    violations.extend(validate_pod(&pod_spec, &context, settings, &exempted));
+                }
             };
-            // If there is not pod spec, just accept it. There is no data to be
+            // If there is not pod spec, there is no container data to be
             // validated.
-            kubewarden::accept_request()
+            if violations.is_empty() {
+                return accept_request_with_warnings(warnings);
+            }
+            enforce(
+                &violations,
+                settings,
+                enforcement,
+                warnings,
+                &validation_request.request.uid,
+            )
         }
         Err(_) => {
             warn!(LOG_DRAIN, "cannot unmarshal resource: this policy does not know how to evaluate this resource; accept it");
@@ -173,6 +1156,7 @@ fn validate(payload: &[u8]) -> CallResult {
 mod tests {
     use super::*;
 
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, LabelSelectorRequirement};
     use kubewarden_policy_sdk::test::Testcase;
 
     #[test]
@@ -182,7 +1166,7 @@ mod tests {
             name: String::from("Valid name"),
             fixture_file: String::from(request_file),
             expected_validation_result: true,
-            settings: Settings {},
+            settings: Settings::default(),
         };
 
         let res = tc.eval(validate).unwrap();
@@ -202,7 +1186,7 @@ mod tests {
             name: String::from("Bad name"),
             fixture_file: String::from(request_file),
             expected_validation_result: false,
-            settings: Settings {},
+            settings: Settings::default(),
         };
 
         let res = tc.eval(validate).unwrap();
@@ -222,7 +1206,7 @@ mod tests {
             name: String::from("Bad name"),
             fixture_file: String::from(request_file),
             expected_validation_result: false,
-            settings: Settings {},
+            settings: Settings::default(),
         };
 
         let res = tc.eval(validate).unwrap();
@@ -242,7 +1226,7 @@ mod tests {
             name: String::from("Valid name"),
             fixture_file: String::from(request_file),
             expected_validation_result: true,
-            settings: Settings {},
+            settings: Settings::default(),
         };
 
         let res = tc.eval(validate).unwrap();
@@ -262,7 +1246,10 @@ mod tests {
             name: String::from("Bad name"),
             fixture_file: String::from(request_file),
             expected_validation_result: false,
-            settings: Settings {},
+            settings: Settings {
+                init_containers: InitContainersMode::Strict,
+                ..Default::default()
+            },
         };
 
         let res = tc.eval(validate).unwrap();
@@ -282,7 +1269,125 @@ mod tests {
             name: String::from("Bad name"),
             fixture_file: String::from(request_file),
             expected_validation_result: false,
-            settings: Settings {},
+            settings: Settings {
+                init_containers: InitContainersMode::Strict,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_without_required_startup() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation.json";
+        let tc = Testcase {
+            name: String::from("Startup probe required"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                required_probes: vec![String::from("startup")],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message.unwrap().contains("without startup probe"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_without_liveness_when_not_required() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Only readiness required"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                required_probes: vec![String::from("readiness")],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_with_startup_probe_when_required() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_startup_probe.json";
+        let tc = Testcase {
+            name: String::from("Startup probe defined"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                startup_probe_mode: StartupProbeMode::Required,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_without_startup_probe_when_required() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation.json";
+        let tc = Testcase {
+            name: String::from("Startup probe missing"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                startup_probe_mode: StartupProbeMode::Required,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message.unwrap().contains("startup probes are required"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_with_short_liveness_delay_in_slow_start_mode() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation.json";
+        let tc = Testcase {
+            name: String::from("Short liveness delay"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                startup_probe_mode: StartupProbeMode::SlowStart,
+                ..Default::default()
+            },
         };
 
         let res = tc.eval(validate).unwrap();
@@ -294,4 +1399,2156 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn reject_pod_with_long_liveness_delay_in_slow_start_mode() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_slow_liveness.json";
+        let tc = Testcase {
+            name: String::from("Long liveness delay without startup probe"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                startup_probe_mode: StartupProbeMode::SlowStart,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message.unwrap().contains("requires a startup probe"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_with_long_liveness_delay_and_startup_probe() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_slow_liveness_startup_probe.json";
+        let tc = Testcase {
+            name: String::from("Long liveness delay with startup probe"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                startup_probe_mode: StartupProbeMode::SlowStart,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_run_to_completion_init_container_without_probes() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_invalid_liveness_init_container.json";
+        let tc = Testcase {
+            name: String::from("Run-to-completion init container"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                init_containers: InitContainersMode::SidecarsOnly,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_sidecar_init_container_without_probes() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_sidecar_init_container.json";
+        let tc = Testcase {
+            name: String::from("Native sidecar without probes"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                init_containers: InitContainersMode::SidecarsOnly,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message.unwrap().contains(
+                "init container log-shipper is invalid at /spec/initContainers/1/livenessProbe"
+            ),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_sidecar_init_container_when_skipped() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_sidecar_init_container.json";
+        let tc = Testcase {
+            name: String::from("Init containers skipped"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                init_containers: InitContainersMode::Skip,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_ephemeral_container_without_probes() -> Result<(), ()> {
+        let request_file = "test_data/pod_update_ephemeral_container.json";
+        let tc = Testcase {
+            name: String::from("Ephemeral container allowed"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_ephemeral_container_when_rejected() -> Result<(), ()> {
+        let request_file = "test_data/pod_update_ephemeral_container.json";
+        let tc = Testcase {
+            name: String::from("Ephemeral containers rejected"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                ephemeral_containers: EphemeralContainersMode::Reject,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("ephemeral container debugger is not allowed"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_ephemeral_container_for_allowed_group() -> Result<(), ()> {
+        let request_file = "test_data/pod_update_ephemeral_container.json";
+        let tc = Testcase {
+            name: String::from("Ephemeral container allowed for group"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                ephemeral_containers: EphemeralContainersMode::AllowGroups,
+                ephemeral_containers_allowed_groups: vec![String::from("system:authenticated")],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_ephemeral_container_for_other_group() -> Result<(), ()> {
+        let request_file = "test_data/pod_update_ephemeral_container.json";
+        let tc = Testcase {
+            name: String::from("Ephemeral container denied for group"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                ephemeral_containers: EphemeralContainersMode::AllowGroups,
+                ephemeral_containers_allowed_groups: vec![String::from("sre")],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("ephemeral container debugger is not allowed"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_ephemeral_container_on_pod_without_probes() -> Result<(), ()> {
+        let request_file = "test_data/pod_update_ephemeral_container_without_probes.json";
+        let tc = Testcase {
+            name: String::from("Debugging a pod without probes"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_ephemeral_container_on_pod_without_probes_when_rejected() -> Result<(), ()> {
+        let request_file = "test_data/pod_update_ephemeral_container_without_probes.json";
+        let tc = Testcase {
+            name: String::from("Ephemeral containers rejected on a pod without probes"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                ephemeral_containers: EphemeralContainersMode::Reject,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert_eq!(
+            res.message.unwrap(),
+            "PROBE005 ephemeral-container-not-allowed: ephemeral container debugger is invalid at \
+             /spec/ephemeralContainers/0: ephemeral container debugger is not allowed",
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_in_excluded_namespace() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Excluded namespace"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                excluded_namespaces: vec![String::from("def*")],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_outside_included_namespaces() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Namespace not included"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                included_namespaces: vec![String::from("team-*")],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_in_included_namespace() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Included namespace"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                included_namespaces: vec![String::from("default")],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_not_matching_pod_selector() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_labels_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Pod not selected"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                pod_selector: Some(LabelSelector {
+                    match_labels: Some(BTreeMap::from([(
+                        String::from("app"),
+                        String::from("redis"),
+                    )])),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_matching_pod_selector() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_labels_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Pod selected"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                pod_selector: Some(LabelSelector {
+                    match_expressions: Some(vec![LabelSelectorRequirement {
+                        key: String::from("app"),
+                        operator: String::from("In"),
+                        values: Some(vec![String::from("nginx")]),
+                    }]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_matching_excluded_pod_selector() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_labels_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Pod excluded"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                excluded_pod_selector: Some(LabelSelector {
+                    match_expressions: Some(vec![LabelSelectorRequirement {
+                        key: String::from("probes-policy/skip"),
+                        operator: String::from("Exists"),
+                        values: None,
+                    }]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_with_excluded_containers() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_injected_sidecars.json";
+        let tc = Testcase {
+            name: String::from("Injected sidecars excluded"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                excluded_containers: vec![String::from("istio-*"), String::from("fluent-bit")],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_injected_sidecars() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_injected_sidecars.json";
+        let tc = Testcase {
+            name: String::from("Injected sidecars not excluded"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                excluded_containers: vec![String::from("istio-*")],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("container fluent-bit is invalid at /spec/containers/2"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_excluded_ephemeral_container() -> Result<(), ()> {
+        let request_file = "test_data/pod_update_ephemeral_container.json";
+        let tc = Testcase {
+            name: String::from("Ephemeral container excluded"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                ephemeral_containers: EphemeralContainersMode::Reject,
+                excluded_containers: vec![String::from("debug*")],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("ephemeral container debugger is not allowed"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_with_excluded_images() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_injected_sidecars.json";
+        let tc = Testcase {
+            name: String::from("Injected sidecar images excluded"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                excluded_images: vec![
+                    String::from("istio/proxyv2"),
+                    String::from("fluent/fluent-bit:2.*"),
+                ],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_images_not_excluded() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_injected_sidecars.json";
+        let tc = Testcase {
+            name: String::from("Injected sidecar images not excluded"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                excluded_images: vec![String::from("docker.io/istio/*")],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("container fluent-bit is invalid at /spec/containers/2"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_with_justified_exemption() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_exemption_annotation.json";
+        let tc = Testcase {
+            name: String::from("Justified exemption"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_unjustified_exemption() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_exemption_annotation_no_justification.json";
+        let tc = Testcase {
+            name: String::from("Exemption without justification"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("container migrator is invalid"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_exemption_annotation_not_honored() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_exemption_annotation.json";
+        let tc = Testcase {
+            name: String::from("Exemption annotation not honored"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                exemption_annotations: vec![],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("container migrator is invalid"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_with_unexpired_exemption() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_exemption_annotation_unexpired.json";
+        let tc = Testcase {
+            name: String::from("Unexpired exemption"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.warnings.is_none(),
+            "Unexpected warnings with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_expired_exemption() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_exemption_annotation_expired.json";
+        let tc = Testcase {
+            name: String::from("Expired exemption"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("container migrator is invalid"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_malformed_exemption_deadline() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_exemption_annotation_malformed_deadline.json";
+        let tc = Testcase {
+            name: String::from("Malformed exemption deadline"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("must be an RFC 3339 timestamp"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_with_stray_malformed_exemption_deadline() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_stray_malformed_exemption_deadline.json";
+        let tc = Testcase {
+            name: String::from("Malformed deadline without exemption"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_malformed_deadline_of_exemption_not_honored() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_exemption_annotation_malformed_deadline.json";
+        let tc = Testcase {
+            name: String::from("Malformed deadline of an exemption not honored"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                exemption_annotations: vec![],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        let message = res.message.unwrap();
+        assert!(
+            message.contains("container migrator is invalid")
+                && !message.contains("must be an RFC 3339 timestamp"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn exemption_deadline_warning_window() -> Result<(), ()> {
+        let settings = Settings {
+            exemption_expiry_warning_days: 7,
+            ..Default::default()
+        };
+        let annotations = BTreeMap::from([
+            (
+                String::from(settings::EXEMPT_CONTAINERS_ANNOTATION),
+                String::from("migrator"),
+            ),
+            (
+                String::from(EXEMPT_UNTIL_ANNOTATION),
+                String::from("2026-11-01T00:00:00+02:00"),
+            ),
+        ]);
+        let deadline = DateTime::parse_from_rfc3339("2026-10-31T22:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let at = |now: &str| {
+            exemption_deadline(
+                &annotations,
+                &settings,
+                DateTime::parse_from_rfc3339(now)
+                    .unwrap()
+                    .with_timezone(&Utc),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(at("2026-10-01T00:00:00Z"), ExemptionDeadline::Active);
+        assert_eq!(
+            at("2026-10-28T00:00:00Z"),
+            ExemptionDeadline::ExpiresSoon(deadline)
+        );
+        assert_eq!(
+            at("2026-10-31T22:00:00Z"),
+            ExemptionDeadline::Expired(deadline)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_without_liveness_in_warn_mode() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Warn enforcement"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                enforcement: Enforcement::Warn,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.warnings
+                .unwrap()
+                .iter()
+                .any(|w| w.contains("without liveness probe")),
+            "Missing warning with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_without_liveness_in_dryrun_mode() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Dryrun enforcement"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                enforcement: Enforcement::Dryrun,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.warnings.is_none() && res.message.is_none(),
+            "Unexpected output with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn dryrun_keeps_exemption_expiry_warning() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_exemption_annotation_unexpired.json";
+        let settings = Settings {
+            required_probes: vec![String::from("startup")],
+            enforcement: Enforcement::Dryrun,
+            ..Default::default()
+        };
+
+        let before = validate_fixture_at(request_file, &settings, "2998-12-31T23:59:59Z");
+        assert!(before.accepted);
+        assert_eq!(before.message, None);
+        assert_eq!(
+            before.warnings,
+            Some(vec![String::from(
+                "probe exemptions expire on 2999-01-01T00:00:00+00:00"
+            )])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_without_liveness_before_enforce_after() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Before enforcement switch-over"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                enforce_after: Some(String::from("2999-01-01T00:00:00Z")),
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.warnings.is_some(),
+            "Missing warnings with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_without_probes_reports_every_probe() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_without_probes.json";
+        let tc = Testcase {
+            name: String::from("Missing liveness and readiness"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert_eq!(
+            res.message.unwrap(),
+            "PROBE001 missing-liveness: container nginx is invalid at /spec/containers/0/livenessProbe: \
+             container nginx without liveness probe is not accepted\n\
+             PROBE002 missing-readiness: container nginx is invalid at /spec/containers/0/readinessProbe: \
+             container nginx without readiness probe is not accepted",
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_documentation_links() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Documentation links"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                documentation_url: Some(String::from("https://wiki.example.com/probes")),
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        let message = res.message.unwrap();
+        assert!(
+            message.starts_with("PROBE001 missing-liveness: ")
+                && message.ends_with("(see https://wiki.example.com/probes/PROBE001)"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_message_template() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Message template"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                message_templates: BTreeMap::from([(
+                    String::from("missing-liveness"),
+                    String::from(
+                        "le conteneur {container} du pod {namespace}/{pod} n'a pas de sonde {probe}",
+                    ),
+                )]),
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message.unwrap().ends_with(
+                "le conteneur nginx du pod default/invalid-pod-name n'a pas de sonde liveness"
+            ),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_remediation_snippet() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_invalid_liveness_ports.json";
+        let tc = Testcase {
+            name: String::from("Remediation snippet"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                remediation_snippets: true,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message.unwrap().ends_with(
+                "not accepted\n  \
+                 livenessProbe:\n    \
+                 httpGet:\n      \
+                 path: /healthz\n      \
+                 port: http\n    \
+                 periodSeconds: 10\n    \
+                 timeoutSeconds: 1\n    \
+                 failureThreshold: 3"
+            ),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_deployment_with_probes() -> Result<(), ()> {
+        let request_file = "test_data/deployment_creation.json";
+        let tc = Testcase {
+            name: String::from("Valid Deployment"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_deployment_without_liveness() -> Result<(), ()> {
+        let request_file = "test_data/deployment_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Deployment without liveness probe"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("/spec/template/spec/containers/0/livenessProbe"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_deployment_update_leaving_template_unchanged() -> Result<(), ()> {
+        let request_file = "test_data/deployment_update_scaled_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Deployment scaled without template change"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_deployment_update_changing_template() -> Result<(), ()> {
+        let request_file = "test_data/deployment_update_template_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Deployment template changed"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("/spec/template/spec/containers/0/livenessProbe"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_deployment_being_deleted() -> Result<(), ()> {
+        let request_file = "test_data/deployment_deletion_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Deployment being deleted"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_statefulset_with_probes() -> Result<(), ()> {
+        let request_file = "test_data/statefulset_creation.json";
+        let tc = Testcase {
+            name: String::from("Valid StatefulSet"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_statefulset_without_liveness() -> Result<(), ()> {
+        let request_file = "test_data/statefulset_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("StatefulSet without liveness probe"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("/spec/template/spec/containers/0/livenessProbe"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_daemonset_with_probes() -> Result<(), ()> {
+        let request_file = "test_data/daemonset_creation.json";
+        let tc = Testcase {
+            name: String::from("Valid DaemonSet"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_daemonset_without_liveness() -> Result<(), ()> {
+        let request_file = "test_data/daemonset_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("DaemonSet without liveness probe"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("/spec/template/spec/containers/0/livenessProbe"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_replicaset_with_probes() -> Result<(), ()> {
+        let request_file = "test_data/replicaset_creation.json";
+        let tc = Testcase {
+            name: String::from("Valid ReplicaSet"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_replicaset_without_liveness() -> Result<(), ()> {
+        let request_file = "test_data/replicaset_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("ReplicaSet without liveness probe"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("/spec/template/spec/containers/0/livenessProbe"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_job_with_probes() -> Result<(), ()> {
+        let request_file = "test_data/job_creation.json";
+        let tc = Testcase {
+            name: String::from("Valid Job"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_job_without_liveness() -> Result<(), ()> {
+        let request_file = "test_data/job_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Job without liveness probe"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("/spec/template/spec/containers/0/livenessProbe"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_cronjob_with_probes() -> Result<(), ()> {
+        let request_file = "test_data/cronjob_creation.json";
+        let tc = Testcase {
+            name: String::from("Valid CronJob"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_cronjob_without_liveness() -> Result<(), ()> {
+        let request_file = "test_data/cronjob_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("CronJob without liveness probe"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("/spec/jobTemplate/spec/template/spec/containers/0/livenessProbe"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_job_without_readiness_when_not_required_for_jobs() -> Result<(), ()> {
+        let request_file = "test_data/job_creation_without_readiness.json";
+        let tc = Testcase {
+            name: String::from("Readiness not required for Jobs"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                required_probes_by_kind: BTreeMap::from([(
+                    String::from("Job"),
+                    vec![String::from("liveness")],
+                )]),
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_job_without_readiness_by_default() -> Result<(), ()> {
+        let request_file = "test_data/job_creation_without_readiness.json";
+        let tc = Testcase {
+            name: String::from("Readiness required for Deployments only"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                required_probes_by_kind: BTreeMap::from([(
+                    String::from("Deployment"),
+                    vec![String::from("liveness")],
+                )]),
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("/spec/template/spec/containers/0/readinessProbe"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_job_without_liveness_when_waived() -> Result<(), ()> {
+        let request_file = "test_data/job_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Liveness waived for non-restarting pods"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                non_restarting_pods_liveness: NonRestartingPodsLiveness::Waived,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_without_liveness_when_waived_for_non_restarting_pods() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Liveness required for restarting pods"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                non_restarting_pods_liveness: NonRestartingPodsLiveness::Waived,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message.unwrap().contains("without liveness probe"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_job_with_deadline_without_liveness() -> Result<(), ()> {
+        let request_file = "test_data/job_creation_deadline_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Liveness waived for pods with a deadline"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                non_restarting_pods_liveness: NonRestartingPodsLiveness::WithoutDeadline,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_job_without_deadline_without_liveness() -> Result<(), ()> {
+        let request_file = "test_data/job_creation_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Liveness required for pods without a deadline"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                non_restarting_pods_liveness: NonRestartingPodsLiveness::WithoutDeadline,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("/spec/template/spec/containers/0/livenessProbe"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_with_probe_timings_within_bounds() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation.json";
+        let tc = Testcase {
+            name: String::from("Probe timings within bounds"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                probe_bounds: BTreeMap::from([(
+                    String::from("liveness"),
+                    settings::ProbeBounds {
+                        period_seconds: settings::Bounds {
+                            min: Some(5),
+                            max: Some(60),
+                        },
+                        failure_threshold: settings::Bounds {
+                            min: Some(3),
+                            max: None,
+                        },
+                        ..Default::default()
+                    },
+                )]),
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_probe_timings_out_of_bounds() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_aggressive_liveness.json";
+        let tc = Testcase {
+            name: String::from("Probe timings out of bounds"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                probe_bounds: BTreeMap::from([(
+                    String::from("liveness"),
+                    settings::ProbeBounds {
+                        period_seconds: settings::Bounds {
+                            min: Some(5),
+                            max: None,
+                        },
+                        timeout_seconds: settings::Bounds {
+                            min: None,
+                            max: Some(10),
+                        },
+                        failure_threshold: settings::Bounds {
+                            min: Some(3),
+                            max: None,
+                        },
+                        ..Default::default()
+                    },
+                )]),
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert_eq!(
+            res.message.unwrap(),
+            "PROBE007 probe-timing-out-of-bounds: container nginx is invalid at \
+             /spec/containers/0/livenessProbe/periodSeconds: \
+             container nginx liveness probe periodSeconds of 1 is below the minimum of 5\n\
+             PROBE007 probe-timing-out-of-bounds: container nginx is invalid at \
+             /spec/containers/0/livenessProbe/timeoutSeconds: \
+             container nginx liveness probe timeoutSeconds of 30 is above the maximum of 10\n\
+             PROBE007 probe-timing-out-of-bounds: container nginx is invalid at \
+             /spec/containers/0/livenessProbe/failureThreshold: \
+             container nginx liveness probe failureThreshold of 1 is below the minimum of 3",
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_passing_cross_field_checks() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation.json";
+        let tc = Testcase {
+            name: String::from("Consistent probes"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                cross_field_checks: vec![
+                    String::from("timeout-not-below-period"),
+                    String::from("success-threshold-not-one"),
+                ],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_failing_cross_field_checks() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_inconsistent_probes.json";
+        let tc = Testcase {
+            name: String::from("Inconsistent probes"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                cross_field_checks: Rule::CROSS_FIELD
+                    .iter()
+                    .map(|rule| String::from(rule.id()))
+                    .collect(),
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert_eq!(
+            res.message.unwrap(),
+            "PROBE008 timeout-not-below-period: container nginx is invalid at \
+             /spec/containers/0/livenessProbe/timeoutSeconds: \
+             container nginx liveness probe timeoutSeconds of 5 is not below its periodSeconds of 5\n\
+             PROBE009 success-threshold-not-one: container nginx is invalid at \
+             /spec/containers/0/livenessProbe/successThreshold: \
+             container nginx liveness probe successThreshold of 2 must be 1\n\
+             PROBE010 liveness-window-not-above-readiness: container nginx is invalid at \
+             /spec/containers/0/livenessProbe/failureThreshold: \
+             container nginx liveness failure window of 15s is not above the readiness failure window of 30s: \
+             the container would be restarted before being taken out of rotation",
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_with_large_probe_failure_windows() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_large_probe_thresholds.json";
+        let tc = Testcase {
+            name: String::from("Failure windows beyond i32"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                cross_field_checks: Rule::CROSS_FIELD
+                    .iter()
+                    .map(|rule| String::from(rule.id()))
+                    .collect(),
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_failing_disabled_cross_field_checks() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_inconsistent_probes.json";
+        let tc = Testcase {
+            name: String::from("Cross-field checks disabled"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_with_long_enough_startup_budget() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_slow_liveness_startup_probe.json";
+        let tc = Testcase {
+            name: String::from("Startup budget met"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                startup_budget_seconds: 60,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_with_startup_budget_beyond_i32() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_large_startup_budget.json";
+        let tc = Testcase {
+            name: String::from("Startup budget beyond i32"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                startup_budget_seconds: 60,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_startup_budget_too_short_for_image() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_slow_liveness_startup_probe.json";
+        let tc = Testcase {
+            name: String::from("Startup budget too short for image"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                startup_budget_seconds: 10,
+                startup_budgets_by_image: vec![settings::ImageStartupBudget {
+                    image: String::from("eclipse-temurin:*"),
+                    seconds: 600,
+                }],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("startup probe budget of 300s"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_without_startup_probe_with_short_liveness_delay() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation.json";
+        let tc = Testcase {
+            name: String::from("Liveness delay below startup budget"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                startup_budget_seconds: 10,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert_eq!(
+            res.message.unwrap(),
+            "PROBE011 startup-budget-too-short: container nginx is invalid at \
+             /spec/containers/0/livenessProbe/initialDelaySeconds: \
+             container nginx without startup probe has a liveness probe initialDelaySeconds of 0 \
+             below the startup budget of 10s",
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_with_numeric_probe_ports_in_named_mode() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation.json";
+        let tc = Testcase {
+            name: String::from("Numeric probe ports not checked"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                probe_ports: ProbePortsMode::Named,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_with_undeclared_probe_ports_when_ignored() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_undeclared_probe_ports.json";
+        let tc = Testcase {
+            name: String::from("Probe ports ignored"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_undeclared_named_probe_port() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_undeclared_probe_ports.json";
+        let tc = Testcase {
+            name: String::from("Undeclared named probe port"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                probe_ports: ProbePortsMode::Named,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert_eq!(
+            res.message.unwrap(),
+            "PROBE012 undeclared-probe-port: container app is invalid at \
+             /spec/containers/0/livenessProbe/httpGet/port: \
+             container app liveness probe port healthz does not match the name of a declared container port",
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_undeclared_numeric_probe_port() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_undeclared_probe_ports.json";
+        let tc = Testcase {
+            name: String::from("Undeclared numeric probe port"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                probe_ports: ProbePortsMode::All,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message.unwrap().contains(
+                "/spec/containers/0/readinessProbe/tcpSocket/port: \
+                 container app readiness probe port 9090 is not a declared container port"
+            ),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_invalid_probe_ports() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_invalid_probe_ports.json";
+        let tc = Testcase {
+            name: String::from("Invalid probe ports"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert_eq!(
+            res.message.unwrap(),
+            "PROBE013 invalid-probe-port: container app is invalid at \
+             /spec/containers/0/livenessProbe/httpGet/port: \
+             container app liveness probe port 80800 is not between 1 and 65535\n\
+             PROBE013 invalid-probe-port: container app is invalid at \
+             /spec/containers/0/readinessProbe/httpGet/port: \
+             container app readiness probe port Http_Alt is not a valid port name: \
+             at most 15 lowercase letters, digits and single hyphens, with at least one letter\n\
+             PROBE014 non-tcp-probe-port: container app is invalid at \
+             /spec/containers/0/startupProbe/tcpSocket/port: \
+             container app startup probe port dns is only declared for UDP: probes connect over TCP\n\
+             PROBE013 invalid-probe-port: container resolver is invalid at \
+             /spec/containers/1/livenessProbe/grpc/port: \
+             container resolver liveness probe port 0 is not between 1 and 65535\n\
+             PROBE014 non-tcp-probe-port: container resolver is invalid at \
+             /spec/containers/1/readinessProbe/grpc/port: \
+             container resolver readiness probe port 5353 is only declared for UDP: probes connect over TCP",
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_with_probe_port_declared_for_tcp_and_udp() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_tcp_udp_probe_port.json";
+        let tc = Testcase {
+            name: String::from("Probe port declared for TCP and UDP"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                probe_ports: ProbePortsMode::All,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_exempted_ephemeral_container() -> Result<(), ()> {
+        let request_file = "test_data/pod_update_ephemeral_container_exemption_annotation.json";
+        let tc = Testcase {
+            name: String::from("Ephemeral container exempted through annotations"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                ephemeral_containers: EphemeralContainersMode::Reject,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("ephemeral container debugger is not allowed"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    // Validates the fixture with the settings at the given RFC 3339 time.
+    fn validate_fixture_at(
+        request_file: &str,
+        settings: &Settings,
+        now: &str,
+    ) -> ValidationResponse {
+        let request: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(request_file).unwrap()).unwrap();
+        let payload = serde_json::json!({
+            "settings": settings,
+            "request": request,
+        });
+        let now = DateTime::parse_from_rfc3339(now)
+            .unwrap()
+            .with_timezone(&Utc);
+        let response = validate_at(payload.to_string().as_bytes(), now).unwrap();
+        serde_json::from_slice(&response).unwrap()
+    }
+
+    #[test]
+    fn enforce_after_boundary() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_invalid_liveness.json";
+        let settings = Settings {
+            enforce_after: Some(String::from("2026-11-01T00:00:00Z")),
+            ..Default::default()
+        };
+
+        let before = validate_fixture_at(request_file, &settings, "2026-10-31T23:59:59Z");
+        assert!(before.accepted && before.warnings.is_some());
+        let at = validate_fixture_at(request_file, &settings, "2026-11-01T00:00:00Z");
+        assert!(!at.accepted);
+
+        Ok(())
+    }
+
+    #[test]
+    fn exemption_deadline_boundary() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_exemption_annotation_unexpired.json";
+        let settings = Settings::default();
+
+        let before = validate_fixture_at(request_file, &settings, "2998-12-31T23:59:59Z");
+        assert!(before.accepted);
+        assert_eq!(
+            before.warnings,
+            Some(vec![String::from(
+                "probe exemptions expire on 2999-01-01T00:00:00+00:00"
+            )])
+        );
+        let at = validate_fixture_at(request_file, &settings, "2999-01-01T00:00:00Z");
+        assert!(!at.accepted);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_unjustified_exemption_without_expiry_warning() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_exemption_annotation_unexpired_unjustified.json";
+        let settings = Settings::default();
+
+        let before = validate_fixture_at(request_file, &settings, "2998-12-31T23:59:59Z");
+        assert!(!before.accepted);
+        assert_eq!(before.warnings, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_generated_pod_with_message_template() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_generate_name_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Message template for a generated name"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                message_templates: BTreeMap::from([(
+                    String::from("missing-liveness"),
+                    String::from("pod {namespace}/{pod}: no {probe} probe on {container}"),
+                )]),
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .ends_with("pod default/web-: no liveness probe on nginx"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_deployment_with_excluded_top_level_labels() -> Result<(), ()> {
+        let request_file = "test_data/deployment_creation_template_labels_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Exclusion label on the Deployment only"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                excluded_pod_selector: Some(LabelSelector {
+                    match_expressions: Some(vec![LabelSelectorRequirement {
+                        key: String::from("probes-policy/skip"),
+                        operator: String::from("Exists"),
+                        values: None,
+                    }]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("/spec/template/spec/containers/0/livenessProbe"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_deployment_matching_pod_selector_on_template_labels() -> Result<(), ()> {
+        let request_file = "test_data/deployment_creation_template_labels_invalid_liveness.json";
+        let tc = Testcase {
+            name: String::from("Pod selector matching the template labels"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                pod_selector: Some(LabelSelector {
+                    match_labels: Some(BTreeMap::from([(
+                        String::from("app"),
+                        String::from("shop"),
+                    )])),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.message
+                .unwrap()
+                .contains("/spec/template/spec/containers/0/livenessProbe"),
+            "Unexpected message with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_job_pod_without_readiness_when_not_required_for_jobs() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_job_owned_without_readiness.json";
+        let tc = Testcase {
+            name: String::from("Readiness not required for Job pods"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                required_probes_by_kind: BTreeMap::from([(
+                    String::from("Job"),
+                    vec![String::from("liveness")],
+                )]),
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accept_job_pod_without_readiness_when_required_for_pods() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_job_owned_without_readiness.json";
+        let tc = Testcase {
+            name: String::from("Job pods resolved to Job"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                required_probes_by_kind: BTreeMap::from([(
+                    String::from("Pod"),
+                    vec![String::from("liveness"), String::from("readiness")],
+                )]),
+                required_probes: vec![String::from("liveness")],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert!(
+            res.mutated_object.is_none(),
+            "Something mutated with test case: {}",
+            tc.name,
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn workload_kinds_from_owner_references() -> Result<(), ()> {
+        let owned = |kind: &str, labels: serde_json::Value| {
+            serde_json::json!({
+                "metadata": {
+                    "labels": labels,
+                    "ownerReferences": [
+                        {"apiVersion": "v1", "kind": "Node", "name": "n", "uid": "1"},
+                        {"apiVersion": "apps/v1", "kind": kind, "name": "o", "uid": "2", "controller": true}
+                    ]
+                }
+            })
+        };
+        let hashed = serde_json::json!({ "pod-template-hash": "7d4b9c" });
+
+        assert_eq!(
+            workload_kind("Pod", &owned("ReplicaSet", hashed.clone())),
+            "Deployment"
+        );
+        assert_eq!(
+            workload_kind("Pod", &owned("ReplicaSet", serde_json::json!({}))),
+            "ReplicaSet"
+        );
+        assert_eq!(
+            workload_kind("Pod", &owned("Job", serde_json::json!({}))),
+            "Job"
+        );
+        assert_eq!(
+            workload_kind("ReplicaSet", &owned("Deployment", hashed)),
+            "Deployment"
+        );
+        assert_eq!(
+            workload_kind("Job", &owned("CronJob", serde_json::json!({}))),
+            "CronJob"
+        );
+        assert_eq!(
+            workload_kind("Pod", &owned("Workflow", serde_json::json!({}))),
+            "Pod"
+        );
+        assert_eq!(workload_kind("Pod", &serde_json::json!({})), "Pod");
+        Ok(())
+    }
 }
@@ -0,0 +1,170 @@
+// Copyright (C) Nicolas Lamirault <nicolas.lamirault@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt;
+
+use k8s_openapi::api::core::v1 as apicore;
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+
+// The timing fields of a probe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TimingField {
+    InitialDelaySeconds,
+    PeriodSeconds,
+    TimeoutSeconds,
+    SuccessThreshold,
+    FailureThreshold,
+}
+
+impl TimingField {
+    pub(crate) const ALL: [TimingField; 5] = [
+        TimingField::InitialDelaySeconds,
+        TimingField::PeriodSeconds,
+        TimingField::TimeoutSeconds,
+        TimingField::SuccessThreshold,
+        TimingField::FailureThreshold,
+    ];
+
+    // Name of the probe field.
+    pub(crate) fn field(&self) -> &'static str {
+        match self {
+            TimingField::InitialDelaySeconds => "initialDelaySeconds",
+            TimingField::PeriodSeconds => "periodSeconds",
+            TimingField::TimeoutSeconds => "timeoutSeconds",
+            TimingField::SuccessThreshold => "successThreshold",
+            TimingField::FailureThreshold => "failureThreshold",
+        }
+    }
+
+    // Value of the field, or the value the kubelet uses when it is unset.
+    pub(crate) fn value(&self, probe: &apicore::Probe) -> i32 {
+        match self {
+            TimingField::InitialDelaySeconds => probe.initial_delay_seconds.unwrap_or(0),
+            TimingField::PeriodSeconds => probe.period_seconds.unwrap_or(10),
+            TimingField::TimeoutSeconds => probe.timeout_seconds.unwrap_or(1),
+            TimingField::SuccessThreshold => probe.success_threshold.unwrap_or(1),
+            TimingField::FailureThreshold => probe.failure_threshold.unwrap_or(3),
+        }
+    }
+}
+
+impl fmt::Display for TimingField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.field())
+    }
+}
+
+// The port the probe handler connects to, with the path of the field holding
+// it relative to the probe. Exec probes have none.
+pub(crate) fn port(probe: &apicore::Probe) -> Option<(&'static str, IntOrString)> {
+    if let Some(http_get) = &probe.http_get {
+        return Some(("httpGet/port", http_get.port.clone()));
+    }
+    if let Some(tcp_socket) = &probe.tcp_socket {
+        return Some(("tcpSocket/port", tcp_socket.port.clone()));
+    }
+    probe
+        .grpc
+        .as_ref()
+        .map(|grpc| ("grpc/port", IntOrString::Int(grpc.port)))
+}
+
+// Returns true if the name is a valid port name, i.e. an IANA_SVC_NAME: at
+// most 15 lowercase letters, digits and hyphens, with at least one letter and
+// neither leading, trailing nor consecutive hyphens.
+pub(crate) fn valid_port_name(name: &str) -> bool {
+    (1..=15).contains(&name.len())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && name.chars().any(|c| c.is_ascii_lowercase())
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+        && !name.contains("--")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timing_defaults() {
+        let probe = apicore::Probe::default();
+        let values: Vec<i32> = TimingField::ALL.iter().map(|f| f.value(&probe)).collect();
+        assert_eq!(values, vec![0, 10, 1, 1, 3]);
+    }
+
+    #[test]
+    fn timing_values() {
+        let probe = apicore::Probe {
+            initial_delay_seconds: Some(15),
+            period_seconds: Some(5),
+            timeout_seconds: Some(2),
+            success_threshold: Some(1),
+            failure_threshold: Some(6),
+            ..Default::default()
+        };
+        let values: Vec<i32> = TimingField::ALL.iter().map(|f| f.value(&probe)).collect();
+        assert_eq!(values, vec![15, 5, 2, 1, 6]);
+    }
+
+    #[test]
+    fn handler_ports() {
+        let probe = apicore::Probe {
+            http_get: Some(apicore::HTTPGetAction {
+                port: IntOrString::String(String::from("http")),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            port(&probe),
+            Some(("httpGet/port", IntOrString::String(String::from("http"))))
+        );
+
+        let probe = apicore::Probe {
+            grpc: Some(apicore::GRPCAction {
+                port: 9090,
+                service: None,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(port(&probe), Some(("grpc/port", IntOrString::Int(9090))));
+
+        let probe = apicore::Probe {
+            exec: Some(apicore::ExecAction {
+                command: Some(vec![String::from("true")]),
+            }),
+            ..Default::default()
+        };
+        assert_eq!(port(&probe), None);
+    }
+
+    #[test]
+    fn port_names() {
+        assert!(valid_port_name("http"));
+        assert!(valid_port_name("metrics-9090"));
+        assert!(valid_port_name("h2c"));
+        assert!(!valid_port_name(""));
+        assert!(!valid_port_name("8080"));
+        assert!(!valid_port_name("HTTP"));
+        assert!(!valid_port_name("-http"));
+        assert!(!valid_port_name("http-"));
+        assert!(!valid_port_name("http--alt"));
+        assert!(!valid_port_name("http_alt"));
+        assert!(!valid_port_name("a-very-long-port-name"));
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (C) Nicolas Lamirault <nicolas.lamirault@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use k8s_openapi::api::core::v1 as apicore;
+
+use crate::settings::ProbeKind;
+
+// Port numbers commonly serving HTTP.
+const HTTP_PORTS: [i32; 6] = [80, 443, 3000, 8000, 8080, 8443];
+
+// Returns true if the port looks like it serves HTTP, from its name or number.
+fn http_hint(port: &apicore::ContainerPort) -> bool {
+    let named_http = port.name.as_deref().is_some_and(|name| {
+        name.starts_with("http") || name.starts_with("web") || name == "metrics"
+    });
+    named_http || HTTP_PORTS.contains(&port.container_port)
+}
+
+// Returns a ready-to-paste YAML probe definition for the container: an
+// httpGet probe against its first TCP port when it looks like HTTP, a
+// tcpSocket probe against it otherwise, and an exec probe with a placeholder
+// command when the container declares no TCP port.
+pub(crate) fn probe_snippet(container: &apicore::Container, kind: ProbeKind) -> String {
+    let first_tcp_port = container.ports.iter().flatten().find(|port| {
+        port.protocol
+            .as_deref()
+            .is_none_or(|protocol| protocol == "TCP")
+    });
+    let handler = match first_tcp_port {
+        Some(port) => {
+            let port_ref = port
+                .name
+                .clone()
+                .unwrap_or_else(|| port.container_port.to_string());
+            if http_hint(port) {
+                let path = match kind {
+                    ProbeKind::Readiness => "/ready",
+                    ProbeKind::Liveness | ProbeKind::Startup => "/healthz",
+                };
+                format!("  httpGet:\n    path: {}\n    port: {}\n", path, port_ref)
+            } else {
+                format!("  tcpSocket:\n    port: {}\n", port_ref)
+            }
+        }
+        // There is no generic health check command: leave a placeholder that
+        // must be replaced rather than one that looks like it works.
+        None => String::from(
+            "  exec:\n    command:\n    # replace with a real health check\n    - <health-check-command>\n",
+        ),
+    };
+    let timings = match kind {
+        ProbeKind::Startup => "  periodSeconds: 10\n  failureThreshold: 30\n",
+        ProbeKind::Liveness | ProbeKind::Readiness => {
+            "  periodSeconds: 10\n  timeoutSeconds: 1\n  failureThreshold: 3\n"
+        }
+    };
+    format!("{}:\n{}{}", kind.field(), handler, timings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container(ports: Vec<apicore::ContainerPort>) -> apicore::Container {
+        apicore::Container {
+            name: String::from("app"),
+            ports: Some(ports),
+            ..Default::default()
+        }
+    }
+
+    fn port(name: Option<&str>, number: i32, protocol: Option<&str>) -> apicore::ContainerPort {
+        apicore::ContainerPort {
+            name: name.map(String::from),
+            container_port: number,
+            protocol: protocol.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn http_get_snippet() {
+        let container = container(vec![
+            port(Some("dns"), 53, Some("UDP")),
+            port(Some("http"), 8080, None),
+        ]);
+
+        assert_eq!(
+            probe_snippet(&container, ProbeKind::Readiness),
+            "readinessProbe:\n  httpGet:\n    path: /ready\n    port: http\n  \
+             periodSeconds: 10\n  timeoutSeconds: 1\n  failureThreshold: 3\n"
+        );
+    }
+
+    #[test]
+    fn tcp_socket_snippet() {
+        let container = container(vec![port(None, 5432, Some("TCP"))]);
+
+        assert_eq!(
+            probe_snippet(&container, ProbeKind::Liveness),
+            "livenessProbe:\n  tcpSocket:\n    port: 5432\n  \
+             periodSeconds: 10\n  timeoutSeconds: 1\n  failureThreshold: 3\n"
+        );
+    }
+
+    #[test]
+    fn exec_snippet() {
+        let container = container(vec![]);
+
+        assert_eq!(
+            probe_snippet(&container, ProbeKind::Startup),
+            "startupProbe:\n  exec:\n    command:\n    # replace with a real health check\n    \
+             - <health-check-command>\n  \
+             periodSeconds: 10\n  failureThreshold: 30\n"
+        );
+    }
+}
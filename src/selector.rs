@@ -0,0 +1,171 @@
+// Copyright (C) Nicolas Lamirault <nicolas.lamirault@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+
+// Checks the selector is well formed: known operators, values only for
+// `In` and `NotIn`, and non-empty keys.
+pub(crate) fn validate(selector: &LabelSelector) -> Result<(), String> {
+    for requirement in selector.match_expressions.iter().flatten() {
+        if requirement.key.is_empty() {
+            return Err(String::from("matchExpressions key cannot be empty"));
+        }
+        let values = requirement.values.as_deref().unwrap_or_default();
+        match requirement.operator.as_str() {
+            "In" | "NotIn" => {
+                if values.is_empty() {
+                    return Err(format!(
+                        "matchExpressions {} {} requires values",
+                        requirement.key, requirement.operator
+                    ));
+                }
+            }
+            "Exists" | "DoesNotExist" => {
+                if !values.is_empty() {
+                    return Err(format!(
+                        "matchExpressions {} {} cannot have values",
+                        requirement.key, requirement.operator
+                    ));
+                }
+            }
+            operator => {
+                return Err(format!(
+                    "matchExpressions {} has unknown operator {} (expected In, NotIn, Exists or DoesNotExist)",
+                    requirement.key, operator
+                ));
+            }
+        }
+    }
+    for key in selector
+        .match_labels
+        .iter()
+        .flat_map(|labels| labels.keys())
+    {
+        if key.is_empty() {
+            return Err(String::from("matchLabels key cannot be empty"));
+        }
+    }
+    Ok(())
+}
+
+// Returns true if the labels satisfy every requirement of the selector. An
+// empty selector matches everything.
+pub(crate) fn matches(selector: &LabelSelector, labels: &BTreeMap<String, String>) -> bool {
+    let labels_match = selector
+        .match_labels
+        .iter()
+        .flatten()
+        .all(|(key, value)| labels.get(key) == Some(value));
+    let expressions_match = selector
+        .match_expressions
+        .iter()
+        .flatten()
+        .all(|requirement| {
+            let values = requirement.values.as_deref().unwrap_or_default();
+            let label = labels.get(&requirement.key);
+            match requirement.operator.as_str() {
+                "In" => label.is_some_and(|label| values.contains(label)),
+                "NotIn" => label.is_none_or(|label| !values.contains(label)),
+                "Exists" => label.is_some(),
+                "DoesNotExist" => label.is_none(),
+                _ => false,
+            }
+        });
+    labels_match && expressions_match
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelectorRequirement;
+
+    fn requirement(key: &str, operator: &str, values: &[&str]) -> LabelSelectorRequirement {
+        LabelSelectorRequirement {
+            key: String::from(key),
+            operator: String::from(operator),
+            values: if values.is_empty() {
+                None
+            } else {
+                Some(values.iter().map(|v| String::from(*v)).collect())
+            },
+        }
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (String::from(*k), String::from(*v)))
+            .collect()
+    }
+
+    #[test]
+    fn match_labels_and_expressions() {
+        let selector = LabelSelector {
+            match_labels: Some(labels(&[("app", "nginx")])),
+            match_expressions: Some(vec![
+                requirement("tier", "In", &["frontend", "backend"]),
+                requirement("env", "NotIn", &["dev"]),
+                requirement("team", "Exists", &[]),
+                requirement("legacy", "DoesNotExist", &[]),
+            ]),
+        };
+
+        assert!(matches(
+            &selector,
+            &labels(&[("app", "nginx"), ("tier", "frontend"), ("team", "a")])
+        ));
+        assert!(!matches(
+            &selector,
+            &labels(&[
+                ("app", "nginx"),
+                ("tier", "frontend"),
+                ("team", "a"),
+                ("env", "dev")
+            ])
+        ));
+        assert!(!matches(
+            &selector,
+            &labels(&[("app", "nginx"), ("tier", "frontend")])
+        ));
+        assert!(!matches(&selector, &labels(&[("app", "redis")])));
+        assert!(matches(&LabelSelector::default(), &labels(&[])));
+    }
+
+    #[test]
+    fn validate_selector_syntax() {
+        let valid = LabelSelector {
+            match_expressions: Some(vec![requirement("tier", "In", &["frontend"])]),
+            ..Default::default()
+        };
+        assert!(validate(&valid).is_ok());
+
+        for invalid in [
+            requirement("tier", "Equals", &["frontend"]),
+            requirement("tier", "In", &[]),
+            requirement("tier", "Exists", &["frontend"]),
+            requirement("", "Exists", &[]),
+        ] {
+            let selector = LabelSelector {
+                match_expressions: Some(vec![invalid]),
+                ..Default::default()
+            };
+            assert!(validate(&selector).is_err());
+        }
+    }
+}
@@ -14,22 +14,570 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::LOG_DRAIN;
+use std::collections::BTreeMap;
+use std::fmt;
 
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1 as apicore;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
 use serde::{Deserialize, Serialize};
 use slog::info;
 
+use crate::image::{self, ImageReference};
+use crate::probe::TimingField;
+use crate::violation::{self, Rule};
+use crate::LOG_DRAIN;
+use crate::{glob, selector};
+
+// Workload kinds `required_probes_by_kind` accepts.
+pub(crate) const WORKLOAD_KINDS: [&str; 7] = [
+    "Pod",
+    "Deployment",
+    "StatefulSet",
+    "DaemonSet",
+    "Job",
+    "CronJob",
+    "ReplicaSet",
+];
+
+// Annotation listing, comma separated, the containers a pod exempts.
+pub(crate) const EXEMPT_CONTAINERS_ANNOTATION: &str =
+    "probes-policy.kubewarden.io/exempt-containers";
+
+// The kinds of probes a container can be required to define.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ProbeKind {
+    Liveness,
+    Readiness,
+    Startup,
+}
+
+impl ProbeKind {
+    pub(crate) const ALL: [ProbeKind; 3] = [
+        ProbeKind::Liveness,
+        ProbeKind::Readiness,
+        ProbeKind::Startup,
+    ];
+
+    pub(crate) fn parse(kind: &str) -> Option<ProbeKind> {
+        match kind {
+            "liveness" => Some(ProbeKind::Liveness),
+            "readiness" => Some(ProbeKind::Readiness),
+            "startup" => Some(ProbeKind::Startup),
+            _ => None,
+        }
+    }
+
+    // Name of the container field holding the probe.
+    pub(crate) fn field(&self) -> &'static str {
+        match self {
+            ProbeKind::Liveness => "livenessProbe",
+            ProbeKind::Readiness => "readinessProbe",
+            ProbeKind::Startup => "startupProbe",
+        }
+    }
+
+    pub(crate) fn probe<'a>(
+        &self,
+        container: &'a apicore::Container,
+    ) -> Option<&'a apicore::Probe> {
+        match self {
+            ProbeKind::Liveness => container.liveness_probe.as_ref(),
+            ProbeKind::Readiness => container.readiness_probe.as_ref(),
+            ProbeKind::Startup => container.startup_probe.as_ref(),
+        }
+    }
+}
+
+impl fmt::Display for ProbeKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProbeKind::Liveness => write!(f, "liveness"),
+            ProbeKind::Readiness => write!(f, "readiness"),
+            ProbeKind::Startup => write!(f, "startup"),
+        }
+    }
+}
+
+// Inclusive bounds of a probe timing field. Either side is optional.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub(crate) struct Bounds {
+    pub(crate) min: Option<i32>,
+    pub(crate) max: Option<i32>,
+}
+
+// Bounds of the timing fields of a probe kind.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub(crate) struct ProbeBounds {
+    pub(crate) initial_delay_seconds: Bounds,
+    pub(crate) period_seconds: Bounds,
+    pub(crate) timeout_seconds: Bounds,
+    pub(crate) success_threshold: Bounds,
+    pub(crate) failure_threshold: Bounds,
+}
+
+impl ProbeBounds {
+    pub(crate) fn get(&self, field: TimingField) -> Bounds {
+        match field {
+            TimingField::InitialDelaySeconds => self.initial_delay_seconds,
+            TimingField::PeriodSeconds => self.period_seconds,
+            TimingField::TimeoutSeconds => self.timeout_seconds,
+            TimingField::SuccessThreshold => self.success_threshold,
+            TimingField::FailureThreshold => self.failure_threshold,
+        }
+    }
+}
+
+// Minimum startup budget of the containers running a matching image.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ImageStartupBudget {
+    // `[registry/]repository` glob pattern with an optional `:tag` glob or
+    // `@digest`.
+    pub(crate) image: String,
+    pub(crate) seconds: i32,
+}
+
+// Which probe ports must reference a port declared by the container.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ProbePortsMode {
+    // Probe ports are not checked.
+    #[default]
+    Ignore,
+    // Named ports must match the name of a declared port.
+    Named,
+    // Numeric ports must also be declared.
+    All,
+}
+
+// When a startup probe is required on top of `required_probes`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum StartupProbeMode {
+    // Startup probes are only checked through `required_probes`.
+    #[default]
+    Disabled,
+    // Every container must define a startup probe.
+    Required,
+    // A startup probe is required when the liveness probe initial delay
+    // exceeds `startup_probe_delay_threshold`.
+    SlowStart,
+}
+
+// Which init containers the probe rules apply to.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum InitContainersMode {
+    // Init containers are never checked.
+    Skip,
+    // Only restartable init containers (native sidecars) are checked.
+    #[default]
+    SidecarsOnly,
+    // Every init container is checked.
+    Strict,
+}
+
+// Whether ephemeral containers, e.g. the ones added by `kubectl debug`, are
+// admitted. They cannot define probes.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum EphemeralContainersMode {
+    #[default]
+    Allow,
+    Reject,
+    // Only allowed for users in `ephemeral_containers_allowed_groups`.
+    AllowGroups,
+}
+
+// Whether run-to-completion pods, i.e. with a `restartPolicy` of `Never` or
+// `OnFailure`, must define liveness probes.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum NonRestartingPodsLiveness {
+    // Liveness probes are required as for any other pod.
+    #[default]
+    Required,
+    // Liveness probes are never required.
+    Waived,
+    // Liveness probes are only required without `activeDeadlineSeconds`.
+    WithoutDeadline,
+}
+
+// What happens to requests violating the policy.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Enforcement {
+    // Reject the request.
+    #[default]
+    Deny,
+    // Accept the request and return the violations as admission warnings.
+    Warn,
+    // Accept the request and only log the violations.
+    Dryrun,
+}
+
+impl fmt::Display for Enforcement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Enforcement::Deny => write!(f, "deny"),
+            Enforcement::Warn => write!(f, "warn"),
+            Enforcement::Dryrun => write!(f, "dryrun"),
+        }
+    }
+}
+
 // Describe the settings your policy expects when
 // loaded by the policy server.
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 #[serde(default)]
-pub(crate) struct Settings {}
+pub(crate) struct Settings {
+    // Probes every container must define: any of "liveness", "readiness"
+    // and "startup".
+    pub(crate) required_probes: Vec<String>,
+    // Probes required by workload kind, overriding `required_probes` for the
+    // kinds listed. An empty list requires no probe.
+    pub(crate) required_probes_by_kind: BTreeMap<String, Vec<String>>,
+    pub(crate) non_restarting_pods_liveness: NonRestartingPodsLiveness,
+    // Bounds of the probe timing fields by probe kind. Unset fields are
+    // checked against their Kubernetes defaults.
+    pub(crate) probe_bounds: BTreeMap<String, ProbeBounds>,
+    // Relational checks between probe fields to perform, by rule identifier
+    // or name.
+    pub(crate) cross_field_checks: Vec<String>,
+    // Minimum time, in seconds, a container gets to start before its liveness
+    // probe can restart it. 0 disables the check.
+    pub(crate) startup_budget_seconds: i32,
+    // Startup budgets by image, overriding `startup_budget_seconds`. The
+    // first matching image wins.
+    pub(crate) startup_budgets_by_image: Vec<ImageStartupBudget>,
+    pub(crate) probe_ports: ProbePortsMode,
+    pub(crate) startup_probe_mode: StartupProbeMode,
+    // Liveness probe `initialDelaySeconds` above which a startup probe is
+    // required in `slow_start` mode.
+    pub(crate) startup_probe_delay_threshold: i32,
+    pub(crate) init_containers: InitContainersMode,
+    pub(crate) ephemeral_containers: EphemeralContainersMode,
+    pub(crate) ephemeral_containers_allowed_groups: Vec<String>,
+    // Namespaces, exact names or glob patterns, the policy applies to. All
+    // namespaces when empty.
+    pub(crate) included_namespaces: Vec<String>,
+    // Namespaces, exact names or glob patterns, the policy never applies to.
+    pub(crate) excluded_namespaces: Vec<String>,
+    // Only pods matching this label selector are checked. All pods when unset.
+    pub(crate) pod_selector: Option<LabelSelector>,
+    // Pods matching this label selector are never checked.
+    pub(crate) excluded_pod_selector: Option<LabelSelector>,
+    // Containers, by name or glob pattern, that are never checked.
+    pub(crate) excluded_containers: Vec<String>,
+    // Container images that are never checked, as `[registry/]repository`
+    // glob patterns with an optional `:tag` glob or `@digest`.
+    pub(crate) excluded_images: Vec<String>,
+    // Annotations through which a pod can exempt some of its containers.
+    // Empty to disable annotation exemptions.
+    pub(crate) exemption_annotations: Vec<String>,
+    // Days before an exemption deadline from which admitted pods get a
+    // warning.
+    pub(crate) exemption_expiry_warning_days: i64,
+    pub(crate) enforcement: Enforcement,
+    // RFC 3339 timestamp before which the policy only warns.
+    pub(crate) enforce_after: Option<String>,
+    // Base URL of the rules documentation, linked from every violation as
+    // `<documentation_url>/<rule id>`.
+    pub(crate) documentation_url: Option<String>,
+    // Violation messages by rule identifier or name, with `{container}`,
+    // `{namespace}`, `{probe}` and `{pod}` placeholders.
+    pub(crate) message_templates: BTreeMap<String, String>,
+    // Append a YAML probe snippet to missing probe rejections.
+    pub(crate) remediation_snippets: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            required_probes: vec![String::from("liveness"), String::from("readiness")],
+            required_probes_by_kind: BTreeMap::new(),
+            non_restarting_pods_liveness: NonRestartingPodsLiveness::default(),
+            probe_bounds: BTreeMap::new(),
+            cross_field_checks: vec![],
+            startup_budget_seconds: 0,
+            startup_budgets_by_image: vec![],
+            probe_ports: ProbePortsMode::default(),
+            startup_probe_mode: StartupProbeMode::default(),
+            startup_probe_delay_threshold: 30,
+            init_containers: InitContainersMode::default(),
+            ephemeral_containers: EphemeralContainersMode::default(),
+            ephemeral_containers_allowed_groups: vec![],
+            included_namespaces: vec![],
+            excluded_namespaces: vec![],
+            pod_selector: None,
+            excluded_pod_selector: None,
+            excluded_containers: vec![],
+            excluded_images: vec![],
+            exemption_annotations: vec![String::from(EXEMPT_CONTAINERS_ANNOTATION)],
+            exemption_expiry_warning_days: 7,
+            enforcement: Enforcement::default(),
+            enforce_after: None,
+            documentation_url: None,
+            message_templates: BTreeMap::new(),
+            remediation_snippets: false,
+        }
+    }
+}
+
+impl Settings {
+    // Returns the probe kinds required for the given workload kind. Unknown
+    // probe kinds are ignored: they are rejected when the settings are
+    // validated.
+    pub(crate) fn required_probe_kinds(&self, workload_kind: &str) -> Vec<ProbeKind> {
+        let required = self
+            .required_probes_by_kind
+            .get(workload_kind)
+            .unwrap_or(&self.required_probes);
+        let mut kinds = Vec::new();
+        for kind in required.iter().filter_map(|k| ProbeKind::parse(k)) {
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        }
+        kinds
+    }
+
+    // Returns the timing bounds of the probe kind, if any.
+    pub(crate) fn probe_bounds(&self, kind: ProbeKind) -> Option<&ProbeBounds> {
+        self.probe_bounds.get(&kind.to_string())
+    }
+
+    // Returns true if the cross-field check of the rule is enabled.
+    pub(crate) fn cross_field_check(&self, rule: Rule) -> bool {
+        self.cross_field_checks
+            .iter()
+            .any(|check| Rule::parse(check) == Some(rule))
+    }
+
+    // Returns the minimum startup budget of a container running the image.
+    pub(crate) fn startup_budget(&self, image: Option<&str>) -> i32 {
+        image
+            .and_then(|image| {
+                self.startup_budgets_by_image
+                    .iter()
+                    .find(|budget| image::matches(&budget.image, image))
+            })
+            .map_or(self.startup_budget_seconds, |budget| budget.seconds)
+    }
+
+    // Returns the enforcement mode in effect at `now`: `warn` before
+    // `enforce_after`, `enforcement` afterwards.
+    pub(crate) fn effective_enforcement(&self, now: DateTime<Utc>) -> Enforcement {
+        let enforce_after = self
+            .enforce_after
+            .as_deref()
+            .and_then(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok());
+        match enforce_after {
+            Some(enforce_after) if now < enforce_after => Enforcement::Warn,
+            _ => self.enforcement,
+        }
+    }
+
+    // Returns the message template of the rule, if any.
+    pub(crate) fn message_template(&self, rule: Rule) -> Option<&str> {
+        self.message_templates
+            .get(rule.id())
+            .or_else(|| self.message_templates.get(rule.name()))
+            .map(|template| template.as_str())
+    }
+
+    // Returns true if the policy applies to the given namespace.
+    pub(crate) fn namespace_in_scope(&self, namespace: &str) -> bool {
+        if glob::matches_any(&self.excluded_namespaces, namespace) {
+            return false;
+        }
+        self.included_namespaces.is_empty()
+            || glob::matches_any(&self.included_namespaces, namespace)
+    }
+
+    // Returns true if the policy applies to a pod with the given labels.
+    pub(crate) fn labels_in_scope(&self, labels: &BTreeMap<String, String>) -> bool {
+        if let Some(excluded) = &self.excluded_pod_selector {
+            if selector::matches(excluded, labels) {
+                return false;
+            }
+        }
+        self.pod_selector
+            .as_ref()
+            .is_none_or(|included| selector::matches(included, labels))
+    }
+}
 
 impl kubewarden::settings::Validatable for Settings {
     fn validate(&self) -> Result<(), String> {
         info!(LOG_DRAIN, "starting settings validation");
 
-        // TODO: perform settings validation if applies
+        if self.required_probes.is_empty() {
+            return Err(String::from("required_probes cannot be empty"));
+        }
+        let unknown: Vec<&str> = self
+            .required_probes
+            .iter()
+            .filter(|kind| ProbeKind::parse(kind).is_none())
+            .map(|kind| kind.as_str())
+            .collect();
+        if !unknown.is_empty() {
+            return Err(format!(
+                "unknown probe kinds in required_probes: {} (expected liveness, readiness or startup)",
+                unknown.join(", ")
+            ));
+        }
+        for (workload_kind, probes) in &self.required_probes_by_kind {
+            if !WORKLOAD_KINDS.contains(&workload_kind.as_str()) {
+                return Err(format!(
+                    "required_probes_by_kind: unknown workload kind {} (expected {})",
+                    workload_kind,
+                    WORKLOAD_KINDS.join(", ")
+                ));
+            }
+            if let Some(unknown) = probes.iter().find(|kind| ProbeKind::parse(kind).is_none()) {
+                return Err(format!(
+                    "required_probes_by_kind: {}: unknown probe kind {} (expected liveness, readiness or startup)",
+                    workload_kind, unknown
+                ));
+            }
+        }
+        for (kind, bounds) in &self.probe_bounds {
+            if ProbeKind::parse(kind).is_none() {
+                return Err(format!(
+                    "probe_bounds: unknown probe kind {} (expected liveness, readiness or startup)",
+                    kind
+                ));
+            }
+            for field in TimingField::ALL {
+                let Bounds { min, max } = bounds.get(field);
+                if min.is_some_and(|min| min < 0) || max.is_some_and(|max| max < 0) {
+                    return Err(format!(
+                        "probe_bounds: {}: {} bounds cannot be negative",
+                        kind, field
+                    ));
+                }
+                if let (Some(min), Some(max)) = (min, max) {
+                    if min > max {
+                        return Err(format!(
+                            "probe_bounds: {}: {} minimum {} is above the maximum {}",
+                            kind, field, min, max
+                        ));
+                    }
+                }
+            }
+        }
+        if let Some(unknown) = self
+            .cross_field_checks
+            .iter()
+            .find(|check| Rule::parse(check).is_none_or(|rule| !Rule::CROSS_FIELD.contains(&rule)))
+        {
+            return Err(format!(
+                "cross_field_checks: unknown check {} (expected {})",
+                unknown,
+                Rule::CROSS_FIELD.map(|rule| rule.name()).join(", ")
+            ));
+        }
+        if self.startup_budget_seconds < 0 {
+            return Err(String::from("startup_budget_seconds cannot be negative"));
+        }
+        for budget in &self.startup_budgets_by_image {
+            ImageReference::parse(&budget.image)
+                .map_err(|e| format!("invalid startup_budgets_by_image pattern: {}", e))?;
+            if budget.seconds < 0 {
+                return Err(format!(
+                    "startup_budgets_by_image: {}: seconds cannot be negative",
+                    budget.image
+                ));
+            }
+        }
+        if self.startup_probe_delay_threshold < 0 {
+            return Err(String::from(
+                "startup_probe_delay_threshold cannot be negative",
+            ));
+        }
+        if self.ephemeral_containers == EphemeralContainersMode::AllowGroups
+            && self.ephemeral_containers_allowed_groups.is_empty()
+        {
+            return Err(String::from(
+                "ephemeral_containers_allowed_groups cannot be empty when ephemeral_containers is allow_groups",
+            ));
+        }
+        let overlapping: Vec<&str> = self
+            .included_namespaces
+            .iter()
+            .filter(|included| {
+                self.excluded_namespaces
+                    .iter()
+                    .any(|excluded| glob::intersects(included, excluded))
+            })
+            .map(|included| included.as_str())
+            .collect();
+        if !overlapping.is_empty() {
+            return Err(format!(
+                "included_namespaces overlap with excluded_namespaces: {}",
+                overlapping.join(", ")
+            ));
+        }
+        for pattern in &self.excluded_images {
+            ImageReference::parse(pattern)
+                .map_err(|e| format!("invalid excluded_images pattern: {}", e))?;
+        }
+        if self.exemption_annotations.iter().any(|a| a.is_empty()) {
+            return Err(String::from(
+                "exemption_annotations cannot contain empty keys",
+            ));
+        }
+        if !(0..=3650).contains(&self.exemption_expiry_warning_days) {
+            return Err(String::from(
+                "exemption_expiry_warning_days must be between 0 and 3650",
+            ));
+        }
+        if let Some(enforce_after) = &self.enforce_after {
+            DateTime::parse_from_rfc3339(enforce_after).map_err(|e| {
+                format!(
+                    "enforce_after must be an RFC 3339 timestamp, got {:?}: {}",
+                    enforce_after, e
+                )
+            })?;
+        }
+        if let Some(documentation_url) = &self.documentation_url {
+            if !documentation_url.starts_with("https://")
+                && !documentation_url.starts_with("http://")
+            {
+                return Err(format!(
+                    "documentation_url must be an http or https URL, got {:?}",
+                    documentation_url
+                ));
+            }
+        }
+        for (rule, template) in &self.message_templates {
+            if Rule::parse(rule).is_none() {
+                return Err(format!("message_templates: unknown rule {}", rule));
+            }
+            let unknown: Vec<&str> = violation::template_placeholders(template)
+                .map_err(|e| format!("message_templates: {}: {}", rule, e))?
+                .into_iter()
+                .filter(|placeholder| !violation::TEMPLATE_PLACEHOLDERS.contains(placeholder))
+                .collect();
+            if !unknown.is_empty() {
+                return Err(format!(
+                    "message_templates: {}: unknown placeholders {{{}}} (expected {{{}}})",
+                    rule,
+                    unknown.join("}, {"),
+                    violation::TEMPLATE_PLACEHOLDERS.join("}, {")
+                ));
+            }
+        }
+        if let Some(pod_selector) = &self.pod_selector {
+            selector::validate(pod_selector).map_err(|e| format!("invalid pod_selector: {}", e))?;
+        }
+        if let Some(excluded_pod_selector) = &self.excluded_pod_selector {
+            selector::validate(excluded_pod_selector)
+                .map_err(|e| format!("invalid excluded_pod_selector: {}", e))?;
+        }
         Ok(())
     }
 }
@@ -42,9 +590,461 @@ mod tests {
 
     #[test]
     fn validate_settings() -> Result<(), ()> {
-        let settings = Settings {};
+        let settings = Settings::default();
+
+        assert!(settings.validate().is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn validate_settings_with_startup_probe() -> Result<(), ()> {
+        let settings = Settings {
+            required_probes: vec![String::from("startup"), String::from("liveness")],
+            ..Default::default()
+        };
+
+        assert!(settings.validate().is_ok());
+        assert_eq!(
+            settings.required_probe_kinds("Pod"),
+            vec![ProbeKind::Startup, ProbeKind::Liveness]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reject_empty_required_probes() -> Result<(), ()> {
+        let settings = Settings {
+            required_probes: vec![],
+            ..Default::default()
+        };
+
+        assert!(settings.validate().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reject_unknown_probe_kind() -> Result<(), ()> {
+        let settings = Settings {
+            required_probes: vec![String::from("liveness"), String::from("healthz")],
+            ..Default::default()
+        };
+
+        let err = settings.validate().unwrap_err();
+        assert!(err.contains("healthz"), "unexpected error: {}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn required_probes_by_workload_kind() -> Result<(), ()> {
+        let settings = Settings {
+            required_probes_by_kind: BTreeMap::from([
+                (String::from("DaemonSet"), vec![String::from("liveness")]),
+                (String::from("Job"), vec![]),
+            ]),
+            ..Default::default()
+        };
+
+        assert!(settings.validate().is_ok());
+        assert_eq!(
+            settings.required_probe_kinds("DaemonSet"),
+            vec![ProbeKind::Liveness]
+        );
+        assert!(settings.required_probe_kinds("Job").is_empty());
+        assert_eq!(
+            settings.required_probe_kinds("Deployment"),
+            vec![ProbeKind::Liveness, ProbeKind::Readiness]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reject_unknown_workload_kind() -> Result<(), ()> {
+        let settings = Settings {
+            required_probes_by_kind: BTreeMap::from([(
+                String::from("deployment"),
+                vec![String::from("liveness")],
+            )]),
+            ..Default::default()
+        };
+
+        let err = settings.validate().unwrap_err();
+        assert!(err.contains("deployment"), "unexpected error: {}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn reject_unknown_probe_kind_by_workload_kind() -> Result<(), ()> {
+        let settings = Settings {
+            required_probes_by_kind: BTreeMap::from([(
+                String::from("Job"),
+                vec![String::from("healthz")],
+            )]),
+            ..Default::default()
+        };
+
+        let err = settings.validate().unwrap_err();
+        assert!(
+            err.contains("Job: unknown probe kind healthz"),
+            "unexpected error: {}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn validate_probe_bounds() -> Result<(), ()> {
+        let settings = Settings {
+            probe_bounds: BTreeMap::from([(
+                String::from("liveness"),
+                ProbeBounds {
+                    period_seconds: Bounds {
+                        min: Some(5),
+                        max: Some(60),
+                    },
+                    failure_threshold: Bounds {
+                        min: Some(3),
+                        max: None,
+                    },
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+
+        assert!(settings.validate().is_ok());
+        assert_eq!(
+            settings
+                .probe_bounds(ProbeKind::Liveness)
+                .map(|bounds| bounds.get(TimingField::PeriodSeconds)),
+            Some(Bounds {
+                min: Some(5),
+                max: Some(60)
+            })
+        );
+        assert!(settings.probe_bounds(ProbeKind::Readiness).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn reject_inverted_probe_bounds() -> Result<(), ()> {
+        let settings = Settings {
+            probe_bounds: BTreeMap::from([(
+                String::from("readiness"),
+                ProbeBounds {
+                    timeout_seconds: Bounds {
+                        min: Some(10),
+                        max: Some(5),
+                    },
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+
+        let err = settings.validate().unwrap_err();
+        assert!(
+            err.contains("readiness: timeoutSeconds minimum 10 is above the maximum 5"),
+            "unexpected error: {}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reject_probe_bounds_for_unknown_probe_kind() -> Result<(), ()> {
+        let settings = Settings {
+            probe_bounds: BTreeMap::from([(String::from("healthz"), ProbeBounds::default())]),
+            ..Default::default()
+        };
+
+        let err = settings.validate().unwrap_err();
+        assert!(err.contains("healthz"), "unexpected error: {}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn validate_cross_field_checks() -> Result<(), ()> {
+        let settings = Settings {
+            cross_field_checks: vec![
+                String::from("PROBE008"),
+                String::from("liveness-window-not-above-readiness"),
+            ],
+            ..Default::default()
+        };
+
+        assert!(settings.validate().is_ok());
+        assert!(settings.cross_field_check(Rule::TimeoutNotBelowPeriod));
+        assert!(!settings.cross_field_check(Rule::SuccessThresholdNotOne));
+        assert!(settings.cross_field_check(Rule::LivenessWindowNotAboveReadiness));
+        Ok(())
+    }
+
+    #[test]
+    fn reject_unknown_cross_field_check() -> Result<(), ()> {
+        let settings = Settings {
+            cross_field_checks: vec![String::from("missing-liveness")],
+            ..Default::default()
+        };
+
+        let err = settings.validate().unwrap_err();
+        assert!(
+            err.contains("unknown check missing-liveness"),
+            "unexpected error: {}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn startup_budget_by_image() -> Result<(), ()> {
+        let settings = Settings {
+            startup_budget_seconds: 30,
+            startup_budgets_by_image: vec![
+                ImageStartupBudget {
+                    image: String::from("ghcr.io/acme/*-jvm"),
+                    seconds: 120,
+                },
+                ImageStartupBudget {
+                    image: String::from("ghcr.io/acme/*"),
+                    seconds: 10,
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert!(settings.validate().is_ok());
+        assert_eq!(
+            settings.startup_budget(Some("ghcr.io/acme/billing-jvm:3.1")),
+            120
+        );
+        assert_eq!(
+            settings.startup_budget(Some("ghcr.io/acme/gateway:1.0")),
+            10
+        );
+        assert_eq!(settings.startup_budget(Some("nginx")), 30);
+        assert_eq!(settings.startup_budget(None), 30);
+        Ok(())
+    }
+
+    #[test]
+    fn reject_invalid_startup_budget_image() -> Result<(), ()> {
+        let settings = Settings {
+            startup_budgets_by_image: vec![ImageStartupBudget {
+                image: String::from("ghcr.io/acme/app:"),
+                seconds: 60,
+            }],
+            ..Default::default()
+        };
+
+        assert!(settings.validate().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reject_negative_startup_probe_delay_threshold() -> Result<(), ()> {
+        let settings = Settings {
+            startup_probe_mode: StartupProbeMode::SlowStart,
+            startup_probe_delay_threshold: -1,
+            ..Default::default()
+        };
+
+        assert!(settings.validate().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reject_allow_groups_without_groups() -> Result<(), ()> {
+        let settings = Settings {
+            ephemeral_containers: EphemeralContainersMode::AllowGroups,
+            ..Default::default()
+        };
+
+        assert!(settings.validate().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn namespace_scope() -> Result<(), ()> {
+        let settings = Settings {
+            included_namespaces: vec![String::from("team-*"), String::from("default")],
+            excluded_namespaces: vec![String::from("kube-*")],
+            ..Default::default()
+        };
+
+        assert!(settings.validate().is_ok());
+        assert!(settings.namespace_in_scope("default"));
+        assert!(settings.namespace_in_scope("team-a"));
+        assert!(!settings.namespace_in_scope("kube-system"));
+        Ok(())
+    }
+
+    #[test]
+    fn reject_overlapping_namespaces() -> Result<(), ()> {
+        let settings = Settings {
+            included_namespaces: vec![String::from("team-a"), String::from("monitoring")],
+            excluded_namespaces: vec![String::from("team-*")],
+            ..Default::default()
+        };
+
+        let err = settings.validate().unwrap_err();
+        assert!(err.contains("team-a"), "unexpected error: {}", err);
+        assert!(!err.contains("monitoring"), "unexpected error: {}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn reject_intersecting_namespace_patterns() -> Result<(), ()> {
+        let settings = Settings {
+            included_namespaces: vec![String::from("team-*")],
+            excluded_namespaces: vec![String::from("*-ci")],
+            ..Default::default()
+        };
+
+        let err = settings.validate().unwrap_err();
+        assert!(err.contains("team-*"), "unexpected error: {}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn reject_invalid_pod_selector() -> Result<(), ()> {
+        let settings: Settings = serde_json::from_str(
+            r#"{"pod_selector": {"matchExpressions": [{"key": "app", "operator": "Equals", "values": ["nginx"]}]}}"#,
+        )
+        .unwrap();
+
+        let err = settings.validate().unwrap_err();
+        assert!(err.contains("pod_selector"), "unexpected error: {}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn reject_invalid_excluded_image() -> Result<(), ()> {
+        let settings = Settings {
+            excluded_images: vec![String::from("nginx"), String::from("ghcr.io/acme/app:")],
+            ..Default::default()
+        };
+
+        let err = settings.validate().unwrap_err();
+        assert!(
+            err.contains("ghcr.io/acme/app:"),
+            "unexpected error: {}",
+            err
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn enforcement_switch_over() -> Result<(), ()> {
+        let settings = Settings {
+            enforce_after: Some(String::from("2026-11-01T00:00:00+01:00")),
+            ..Default::default()
+        };
+        let at = |now: &str| {
+            settings.effective_enforcement(
+                DateTime::parse_from_rfc3339(now)
+                    .unwrap()
+                    .with_timezone(&Utc),
+            )
+        };
+
+        assert!(settings.validate().is_ok());
+        assert_eq!(at("2026-10-31T22:59:59Z"), Enforcement::Warn);
+        assert_eq!(at("2026-10-31T23:00:00Z"), Enforcement::Deny);
+        assert_eq!(at("2027-01-01T00:00:00Z"), Enforcement::Deny);
+        Ok(())
+    }
+
+    #[test]
+    fn enforcement_without_switch_over() -> Result<(), ()> {
+        let settings = Settings {
+            enforcement: Enforcement::Dryrun,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            settings.effective_enforcement(
+                DateTime::parse_from_rfc3339("2026-10-18T00:00:00Z")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            ),
+            Enforcement::Dryrun
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reject_unparseable_enforce_after() -> Result<(), ()> {
+        let settings = Settings {
+            enforce_after: Some(String::from("2026-11-01")),
+            ..Default::default()
+        };
+
+        let err = settings.validate().unwrap_err();
+        assert!(err.contains("enforce_after"), "unexpected error: {}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn reject_invalid_documentation_url() -> Result<(), ()> {
+        let settings = Settings {
+            documentation_url: Some(String::from("wiki/probes")),
+            ..Default::default()
+        };
+
+        assert!(settings.validate().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn validate_message_templates() -> Result<(), ()> {
+        let settings = Settings {
+            message_templates: BTreeMap::from([
+                (
+                    String::from("PROBE001"),
+                    String::from("le conteneur {container} n'a pas de sonde {probe}"),
+                ),
+                (
+                    String::from("missing-readiness"),
+                    String::from("{pod} in {namespace}: {container} needs readiness"),
+                ),
+            ]),
+            ..Default::default()
+        };
 
         assert!(settings.validate().is_ok());
+        assert_eq!(
+            settings.message_template(Rule::MissingReadinessProbe),
+            Some("{pod} in {namespace}: {container} needs readiness")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reject_unknown_template_placeholder() -> Result<(), ()> {
+        let settings = Settings {
+            message_templates: BTreeMap::from([(
+                String::from("PROBE001"),
+                String::from("{container} in {deployment} lacks {probe}"),
+            )]),
+            ..Default::default()
+        };
+
+        let err = settings.validate().unwrap_err();
+        assert!(err.contains("{deployment}"), "unexpected error: {}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn reject_unknown_template_rule() -> Result<(), ()> {
+        let settings = Settings {
+            message_templates: BTreeMap::from([(
+                String::from("PROBE999"),
+                String::from("{container}"),
+            )]),
+            ..Default::default()
+        };
+
+        assert!(settings.validate().is_err());
         Ok(())
     }
 }
@@ -0,0 +1,420 @@
+// Copyright (C) Nicolas Lamirault <nicolas.lamirault@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt;
+
+use crate::settings::ProbeKind;
+
+// The lists of containers a pod spec holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum ContainerCategory {
+    Container,
+    InitContainer,
+    EphemeralContainer,
+}
+
+impl ContainerCategory {
+    // Name of the pod spec field holding the containers of the category.
+    pub(crate) fn field(&self) -> &'static str {
+        match self {
+            ContainerCategory::Container => "containers",
+            ContainerCategory::InitContainer => "initContainers",
+            ContainerCategory::EphemeralContainer => "ephemeralContainers",
+        }
+    }
+}
+
+impl fmt::Display for ContainerCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ContainerCategory::Container => write!(f, "container"),
+            ContainerCategory::InitContainer => write!(f, "init container"),
+            ContainerCategory::EphemeralContainer => write!(f, "ephemeral container"),
+        }
+    }
+}
+
+// A container of the pod spec, by category and position.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct ContainerRef {
+    pub(crate) category: ContainerCategory,
+    pub(crate) index: usize,
+    pub(crate) name: String,
+    // JSON pointer to the container in the admission object.
+    pub(crate) pointer: String,
+}
+
+impl ContainerRef {
+    pub(crate) fn new(
+        spec_pointer: &str,
+        category: ContainerCategory,
+        index: usize,
+        name: &str,
+    ) -> ContainerRef {
+        ContainerRef {
+            category,
+            index,
+            name: String::from(name),
+            pointer: format!("{}/{}/{}", spec_pointer, category.field(), index),
+        }
+    }
+}
+
+// Escapes a JSON pointer reference token, see RFC 6901.
+pub(crate) fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+// The checks performed by the policy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Rule {
+    MissingLivenessProbe,
+    MissingReadinessProbe,
+    MissingStartupProbe,
+    SlowStartWithoutStartupProbe,
+    EphemeralContainerNotAllowed,
+    InvalidExemptionDeadline,
+    ProbeTimingOutOfBounds,
+    TimeoutNotBelowPeriod,
+    SuccessThresholdNotOne,
+    LivenessWindowNotAboveReadiness,
+    StartupBudgetTooShort,
+    UndeclaredProbePort,
+    InvalidProbePort,
+    NonTcpProbePort,
+}
+
+impl Rule {
+    pub(crate) const ALL: [Rule; 14] = [
+        Rule::MissingLivenessProbe,
+        Rule::MissingReadinessProbe,
+        Rule::MissingStartupProbe,
+        Rule::SlowStartWithoutStartupProbe,
+        Rule::EphemeralContainerNotAllowed,
+        Rule::InvalidExemptionDeadline,
+        Rule::ProbeTimingOutOfBounds,
+        Rule::TimeoutNotBelowPeriod,
+        Rule::SuccessThresholdNotOne,
+        Rule::LivenessWindowNotAboveReadiness,
+        Rule::StartupBudgetTooShort,
+        Rule::UndeclaredProbePort,
+        Rule::InvalidProbePort,
+        Rule::NonTcpProbePort,
+    ];
+
+    // The relational checks between probe fields, enabled one by one through
+    // `cross_field_checks`.
+    pub(crate) const CROSS_FIELD: [Rule; 3] = [
+        Rule::TimeoutNotBelowPeriod,
+        Rule::SuccessThresholdNotOne,
+        Rule::LivenessWindowNotAboveReadiness,
+    ];
+
+    // Finds a rule by identifier or name.
+    pub(crate) fn parse(rule: &str) -> Option<Rule> {
+        Rule::ALL
+            .iter()
+            .find(|r| r.id() == rule || r.name() == rule)
+            .copied()
+    }
+
+    pub(crate) fn missing_probe(kind: ProbeKind) -> Rule {
+        match kind {
+            ProbeKind::Liveness => Rule::MissingLivenessProbe,
+            ProbeKind::Readiness => Rule::MissingReadinessProbe,
+            ProbeKind::Startup => Rule::MissingStartupProbe,
+        }
+    }
+
+    // Stable identifier of the rule, never reused.
+    pub(crate) fn id(&self) -> &'static str {
+        match self {
+            Rule::MissingLivenessProbe => "PROBE001",
+            Rule::MissingReadinessProbe => "PROBE002",
+            Rule::MissingStartupProbe => "PROBE003",
+            Rule::SlowStartWithoutStartupProbe => "PROBE004",
+            Rule::EphemeralContainerNotAllowed => "PROBE005",
+            Rule::InvalidExemptionDeadline => "PROBE006",
+            Rule::ProbeTimingOutOfBounds => "PROBE007",
+            Rule::TimeoutNotBelowPeriod => "PROBE008",
+            Rule::SuccessThresholdNotOne => "PROBE009",
+            Rule::LivenessWindowNotAboveReadiness => "PROBE010",
+            Rule::StartupBudgetTooShort => "PROBE011",
+            Rule::UndeclaredProbePort => "PROBE012",
+            Rule::InvalidProbePort => "PROBE013",
+            Rule::NonTcpProbePort => "PROBE014",
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Rule::MissingLivenessProbe => "missing-liveness",
+            Rule::MissingReadinessProbe => "missing-readiness",
+            Rule::MissingStartupProbe => "missing-startup",
+            Rule::SlowStartWithoutStartupProbe => "slow-start-without-startup",
+            Rule::EphemeralContainerNotAllowed => "ephemeral-container-not-allowed",
+            Rule::InvalidExemptionDeadline => "invalid-exemption-deadline",
+            Rule::ProbeTimingOutOfBounds => "probe-timing-out-of-bounds",
+            Rule::TimeoutNotBelowPeriod => "timeout-not-below-period",
+            Rule::SuccessThresholdNotOne => "success-threshold-not-one",
+            Rule::LivenessWindowNotAboveReadiness => "liveness-window-not-above-readiness",
+            Rule::StartupBudgetTooShort => "startup-budget-too-short",
+            Rule::UndeclaredProbePort => "undeclared-probe-port",
+            Rule::InvalidProbePort => "invalid-probe-port",
+            Rule::NonTcpProbePort => "non-tcp-probe-port",
+        }
+    }
+}
+
+// A problem found in the admission object. Violations not tied to a
+// container, e.g. on the pod annotations, have no `container`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Violation {
+    pub(crate) container: Option<ContainerRef>,
+    pub(crate) rule: Rule,
+    pub(crate) probe: Option<ProbeKind>,
+    // JSON pointer to the offending field in the admission object.
+    pub(crate) path: String,
+    pub(crate) message: String,
+    // YAML snippet fixing the violation.
+    pub(crate) remediation: Option<String>,
+}
+
+impl Violation {
+    pub(crate) fn for_container(
+        container: &ContainerRef,
+        rule: Rule,
+        probe: Option<ProbeKind>,
+        message: String,
+    ) -> Violation {
+        let path = match probe {
+            Some(probe) => format!("{}/{}", container.pointer, probe.field()),
+            None => container.pointer.clone(),
+        };
+        Violation {
+            container: Some(container.clone()),
+            rule,
+            probe,
+            path,
+            message,
+            remediation: None,
+        }
+    }
+
+    pub(crate) fn for_pod(rule: Rule, path: String, message: String) -> Violation {
+        Violation {
+            container: None,
+            rule,
+            probe: None,
+            path,
+            message,
+            remediation: None,
+        }
+    }
+
+    // Narrows the path down to a field of the probe.
+    pub(crate) fn with_field(mut self, field: &str) -> Violation {
+        self.path = format!("{}/{}", self.path, field);
+        self
+    }
+
+    pub(crate) fn with_remediation(mut self, remediation: Option<String>) -> Violation {
+        self.remediation = remediation;
+        self
+    }
+
+    // Describes the violation, linking to the rule documentation under
+    // `documentation_url` when given.
+    pub(crate) fn describe(&self, documentation_url: Option<&str>) -> String {
+        match documentation_url {
+            Some(url) => format!(
+                "{} (see {}/{})",
+                self,
+                url.trim_end_matches('/'),
+                self.rule.id()
+            ),
+            None => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}: ", self.rule.id(), self.rule.name())?;
+        match &self.container {
+            Some(container) => write!(
+                f,
+                "{} {} is invalid at {}: {}",
+                container.category, container.name, self.path, self.message
+            ),
+            None => write!(f, "{}: {}", self.path, self.message),
+        }
+    }
+}
+
+// Renders the violations as a rejection message, one violation per line,
+// pod level violations first, then by container and rule. Remediation
+// snippets follow their violation, indented.
+pub(crate) fn render(violations: &[Violation], documentation_url: Option<&str>) -> String {
+    let mut sorted: Vec<&Violation> = violations.iter().collect();
+    sorted.sort_by(|a, b| (&a.container, a.rule).cmp(&(&b.container, b.rule)));
+    sorted
+        .iter()
+        .map(|violation| {
+            let mut description = violation.describe(documentation_url);
+            for line in violation.remediation.iter().flat_map(|r| r.lines()) {
+                description.push_str("\n  ");
+                description.push_str(line);
+            }
+            description
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// The placeholders message templates can use.
+pub(crate) const TEMPLATE_PLACEHOLDERS: [&str; 4] = ["container", "namespace", "probe", "pod"];
+
+// Returns the placeholders used by a message template, `{name}` each.
+pub(crate) fn template_placeholders(template: &str) -> Result<Vec<&str>, String> {
+    let mut placeholders = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unterminated placeholder in {:?}", template))?;
+        placeholders.push(&rest[start + 1..start + end]);
+        rest = &rest[start + end + 1..];
+    }
+    Ok(placeholders)
+}
+
+// Replaces the `{name}` placeholders of the template with their values.
+pub(crate) fn render_template(template: &str, values: &[(&str, &str)]) -> String {
+    values
+        .iter()
+        .fold(String::from(template), |message, (placeholder, value)| {
+            message.replace(&format!("{{{}}}", placeholder), value)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violation(category: ContainerCategory, index: usize, rule: Rule) -> Violation {
+        let container = ContainerRef::new("/spec", category, index, &format!("c{}", index));
+        Violation::for_container(&container, rule, None, String::from(rule.name()))
+    }
+
+    #[test]
+    fn render_is_deterministic() {
+        let violations = vec![
+            violation(
+                ContainerCategory::InitContainer,
+                0,
+                Rule::MissingLivenessProbe,
+            ),
+            violation(ContainerCategory::Container, 1, Rule::MissingReadinessProbe),
+            violation(ContainerCategory::Container, 1, Rule::MissingLivenessProbe),
+            Violation::for_pod(
+                Rule::InvalidExemptionDeadline,
+                String::from("/metadata/annotations/deadline"),
+                String::from("bad deadline"),
+            ),
+        ];
+
+        assert_eq!(
+            render(&violations, None),
+            "PROBE006 invalid-exemption-deadline: /metadata/annotations/deadline: bad deadline\n\
+             PROBE001 missing-liveness: container c1 is invalid at /spec/containers/1: missing-liveness\n\
+             PROBE002 missing-readiness: container c1 is invalid at /spec/containers/1: missing-readiness\n\
+             PROBE001 missing-liveness: init container c0 is invalid at /spec/initContainers/0: missing-liveness"
+        );
+    }
+
+    #[test]
+    fn container_pointers() {
+        let container = ContainerRef::new(
+            "/spec/template/spec",
+            ContainerCategory::InitContainer,
+            2,
+            "init",
+        );
+        let violation = Violation::for_container(
+            &container,
+            Rule::MissingReadinessProbe,
+            Some(ProbeKind::Readiness),
+            String::from("missing"),
+        );
+
+        assert_eq!(
+            violation.path,
+            "/spec/template/spec/initContainers/2/readinessProbe"
+        );
+        assert_eq!(
+            violation.with_field("periodSeconds").path,
+            "/spec/template/spec/initContainers/2/readinessProbe/periodSeconds"
+        );
+        assert_eq!(
+            escape_pointer_token("probes-policy.kubewarden.io/exempt-until"),
+            "probes-policy.kubewarden.io~1exempt-until"
+        );
+    }
+
+    #[test]
+    fn describe_with_documentation_url() {
+        let violation = violation(ContainerCategory::Container, 0, Rule::MissingLivenessProbe);
+
+        assert_eq!(
+            violation.describe(Some("https://wiki.example.com/probes/")),
+            "PROBE001 missing-liveness: container c0 is invalid at /spec/containers/0: \
+             missing-liveness (see https://wiki.example.com/probes/PROBE001)"
+        );
+    }
+
+    #[test]
+    fn templates() {
+        let template = "le conteneur {container} du pod {pod} n'a pas de sonde {probe}";
+
+        assert_eq!(
+            template_placeholders(template).unwrap(),
+            vec!["container", "pod", "probe"]
+        );
+        assert!(template_placeholders("missing {probe").is_err());
+        assert_eq!(
+            render_template(
+                template,
+                &[
+                    ("container", "nginx"),
+                    ("pod", "web"),
+                    ("probe", "liveness")
+                ]
+            ),
+            "le conteneur nginx du pod web n'a pas de sonde liveness"
+        );
+    }
+
+    #[test]
+    fn parse_rules() {
+        assert_eq!(Rule::parse("PROBE002"), Some(Rule::MissingReadinessProbe));
+        assert_eq!(
+            Rule::parse("missing-readiness"),
+            Some(Rule::MissingReadinessProbe)
+        );
+        assert_eq!(Rule::parse("PROBE999"), None);
+    }
+}